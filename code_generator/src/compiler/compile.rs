@@ -1,12 +1,25 @@
+use std::collections::{ HashMap, HashSet, };
+use std::path::PathBuf;
+
 use ai_dsl2_compiler::{ Block, FunctionKey, Module, Type, Value, };
 use pest::iterators::{ Pair, Pairs, };
 
 use crate::compiler::{
+	Constant,
+	ConstantDeclaration,
+	Delete,
+	EnumDeclaration,
+	ExternDeclaration,
 	ForLoop,
 	Function,
 	FunctionCall,
 	IfStatement,
+	ImplBlock,
+	Import,
 	LearnedValue,
+	LoopContext,
+	LoopControl,
+	MatchStatement,
 	Math,
 	NewStruct,
 	Return,
@@ -21,10 +34,17 @@ use crate::parser::{ self, DSLParser };
 pub struct CompilationContext<'a> {
 	pub airt_handle_function_call: FunctionKey,
 	pub airt_finish_function_call: FunctionKey,
+	pub constants: HashMap<String, Constant>, // global constants
 	pub current_block: Option<Block>,
 	pub current_function: Option<FunctionKey>,
+	pub deleted_variables: HashSet<String>, // variables of the function that is being compiled that were deleted
+	pub file_stack: Vec<PathBuf>, // the file that is currently being compiled is last
+	pub imported_files: HashSet<PathBuf>,
+	pub local_constants: HashMap<String, Constant>, // constants of the function that is being compiled
+	pub loop_stack: Vec<LoopContext>, // innermost loop is last
 	pub module: Module,
 	pub parser: DSLParser<'a>,
+	pub placeholder_evaluation_choice: FunctionKey,
 	pub placeholder_evaluation_float: FunctionKey,
 	pub placeholder_evaluation_int: FunctionKey,
 	// used to determine if we should insert an airt function call before every return in a function. TODO rethink how
@@ -41,43 +61,74 @@ impl CompilationContext<'_> {
 		);
 
 		module.create_extern_function(
-			"_airt_print_float", &vec![Type::Float(0)], Type::Void(0)
+			"strcmp", &vec![Type::CString(0), Type::CString(0)], Type::Integer(0, 32)
 		);
 
 		module.create_extern_function(
-			"_airt_print_int", &vec![Type::Integer(0, 64)], Type::Void(0)
+			"puts", &vec![Type::CString(0)], Type::Integer(0, 32)
 		);
 
 		module.create_extern_function(
-			"_airt_random_float", &vec![Type::Float(0), Type::Float(0)], Type::Float(0)
+			"_airt_print_float", &vec![Type::Float(0, 64)], Type::Void(0)
 		);
 
 		module.create_extern_function(
-			"_airt_log_simulation", &vec![Type::Float(0), Type::Float(0)], Type::Void(0)
+			"_airt_print_int", &vec![Type::Integer(0, 64)], Type::Void(0)
 		);
 
 		CompilationContext {
 			airt_handle_function_call: module.create_extern_function(
-				"airt_handle_function_call", &vec![Type::CString(0), Type::Float(1)], Type::Integer(0, 64)
+				"airt_handle_function_call", &vec![Type::CString(0), Type::Void(1)], Type::Integer(0, 64)
 			),
 			airt_finish_function_call: module.create_extern_function(
 				"airt_finish_function_call", &vec![Type::CString(0), Type::Integer(0, 64)], Type::Void(0)
 			),
+			placeholder_evaluation_choice: module.create_extern_function(
+				"airt_predict_choice",
+				&vec![Type::CString(0), Type::Integer(0, 64), Type::Integer(0, 64), Type::Integer(0, 64)],
+				Type::Integer(0, 64)
+			),
 			placeholder_evaluation_float: module.create_extern_function(
-				"airt_predict_float", &vec![Type::CString(0), Type::Integer(0, 64), Type::Integer(0, 64)], Type::Float(0)
+				"airt_predict_float",
+				&vec![Type::CString(0), Type::Integer(0, 64), Type::Integer(0, 64), Type::Float(0, 64), Type::Float(0, 64)],
+				Type::Float(0, 64)
 			),
 			placeholder_evaluation_int: module.create_extern_function(
-				"airt_predict_int", &vec![Type::CString(0), Type::Integer(0, 64), Type::Integer(0, 64)], Type::Integer(0, 64)
+				"airt_predict_int",
+				&vec![Type::CString(0), Type::Integer(0, 64), Type::Integer(0, 64), Type::Integer(0, 64), Type::Integer(0, 64)],
+				Type::Integer(0, 64)
 			),
 
+			constants: HashMap::new(),
 			current_block: None,
 			current_function: None,
+			deleted_variables: HashSet::new(),
+			file_stack: Vec::new(),
+			imported_files: HashSet::new(),
+			local_constants: HashMap::new(),
+			loop_stack: Vec::new(),
 			module,
 			parser: state.parse_file(&input_filename),
 			prediction_index: None,
 		}
 	}
 
+	// arguments & local variables shadow constants, and local constants shadow global constants
+	pub fn get_constant(&self, name: &str) -> Option<Constant> {
+		if self.current_block.is_some_and(|block| self.module.has_local_variable(block, name)) {
+			return None;
+		}
+
+		self.local_constants.get(name).or_else(|| self.constants.get(name)).copied()
+	}
+
+	// deletes are tracked in the order they are compiled, so a delete in one branch also warns in the other
+	pub fn check_deleted(&self, name: &str) {
+		if self.deleted_variables.contains(name) {
+			eprintln!("warning: `{}` may be used after it was deleted", name);
+		}
+	}
+
 	pub fn add_finish_function_call(&mut self) {
 		if let Some(prediction_index) = self.prediction_index {
 			let function_name = &self.current_function.as_ref().unwrap().name;
@@ -95,7 +146,28 @@ impl CompilationContext<'_> {
 }
 
 pub fn compile_pair(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Option<Value> {
+	if let Some(file) = context.file_stack.last() {
+		let (line, column) = pair.line_col();
+		context.module.set_source_location(format!("{}:{}:{}", file.display(), line, column));
+	}
+
 	match pair.as_rule() {
+		parser::Rule::constant_declaration => {
+			ConstantDeclaration::compile(context, pair);
+			return None;
+		},
+		parser::Rule::delete_statement => {
+			Delete::compile(context, pair);
+			return None;
+		},
+		parser::Rule::enum_declaration => {
+			EnumDeclaration::compile(context, pair);
+			return None;
+		},
+		parser::Rule::extern_function | parser::Rule::extern_variable => {
+			ExternDeclaration::compile(context, pair);
+			return None;
+		},
 		parser::Rule::for_loop => {
 			ForLoop::compile(context, pair);
 			return None;
@@ -111,8 +183,30 @@ pub fn compile_pair(context: &mut CompilationContext, pair: Pair<parser::Rule>)
 			IfStatement::compile(context, pair);
 			return None;
 		},
+		parser::Rule::impl_block => {
+			ImplBlock::compile(context, pair);
+			return None;
+		},
+		parser::Rule::import_statement => {
+			Import::compile(context, pair);
+			return None;
+		},
+		parser::Rule::increment_statement => {
+			Some(VariableAssignment::compile_increment(context, pair))
+		},
+		parser::Rule::index_assignment => {
+			Some(VariableAssignment::compile(context, pair))
+		},
 		parser::Rule::learned_value => {
-			return Some(LearnedValue::compile(context));
+			return Some(LearnedValue::compile(context, pair));
+		},
+		parser::Rule::loop_control => {
+			LoopControl::compile(context, pair);
+			return None;
+		},
+		parser::Rule::match_statement => {
+			MatchStatement::compile(context, pair);
+			return None;
 		},
 		parser::Rule::math => {
 			Some(Math::compile(context, pair))
@@ -137,6 +231,10 @@ pub fn compile_pair(context: &mut CompilationContext, pair: Pair<parser::Rule>)
 		parser::Rule::variable_assignment => {
 			Some(VariableAssignment::compile(context, pair))
 		},
+		parser::Rule::tuple_declaration => {
+			VariableDeclaration::compile_tuple(context, pair);
+			return None;
+		},
 		parser::Rule::variable_declaration => {
 			VariableDeclaration::compile(context, pair);
 			return None;
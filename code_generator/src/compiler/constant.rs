@@ -0,0 +1,206 @@
+use ai_dsl2_compiler::{ MathError, Module, Type, Value, };
+
+use crate::parser;
+
+// a value that is known at compile time. constants are folded into immediates instead of being stored anywhere
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Constant {
+	Boolean(bool),
+	Float(f64),
+	Float32(f32),
+	Integer(i64),
+}
+
+impl Constant {
+	pub fn get_type(&self) -> Type {
+		match *self {
+			Constant::Boolean(_) => Type::Integer(0, 1),
+			Constant::Float(_) => Type::Float(0, 64),
+			Constant::Float32(_) => Type::Float(0, 32),
+			Constant::Integer(_) => Type::Integer(0, 64),
+		}
+	}
+
+	pub fn to_value(&self, module: &Module) -> Value {
+		match *self {
+			Constant::Boolean(value) => module.create_immediate_boolean(value),
+			Constant::Float(number) => module.create_immediate_float(number),
+			Constant::Float32(number) => module.create_immediate_float32(number),
+			Constant::Integer(number) => module.create_immediate_integer(number),
+		}
+	}
+
+	// booleans are promoted to 0 and 1, like the module promotes them
+	pub fn as_float(&self) -> f64 {
+		match *self {
+			Constant::Boolean(value) => value as i64 as f64,
+			Constant::Float(number) => number,
+			Constant::Float32(number) => number as f64,
+			Constant::Integer(number) => number as f64,
+		}
+	}
+
+	pub fn as_integer(&self) -> Option<i64> {
+		match *self {
+			Constant::Boolean(_) | Constant::Float(_) | Constant::Float32(_) => None,
+			Constant::Integer(number) => Some(number),
+		}
+	}
+
+	// what the value means as a condition
+	pub fn as_boolean(&self) -> bool {
+		match *self {
+			Constant::Boolean(value) => value,
+			Constant::Float(number) => number != 0.0,
+			Constant::Float32(number) => number != 0.0,
+			Constant::Integer(number) => number != 0,
+		}
+	}
+
+	// integers may become floats, but floats may not become integers. float literals may be narrowed into 32 bit floats.
+	// integers have to fit into the integer type they are converted to
+	pub fn convert_to_type(&self, type_enum: Type) -> Result<Constant, MathError> {
+		match (*self, type_enum) {
+			(Constant::Boolean(_), Type::Integer(0, 1)) | (Constant::Float(_), Type::Float(0, 64)) => Ok(*self),
+			(Constant::Integer(number), Type::Integer(0, bits) | Type::UnsignedInteger(0, bits))
+				if bits > 1 && Constant::fits_integer(number, type_enum) => Ok(*self),
+			(Constant::Float32(_) | Constant::Integer(_), Type::Float(0, 64)) => Ok(Constant::Float(self.as_float())),
+			(Constant::Float(_) | Constant::Float32(_) | Constant::Integer(_), Type::Float(0, 32)) => {
+				Ok(Constant::Float32(self.as_float() as f32))
+			},
+			_ => Err(MathError::IncompatibleTypes(type_enum, self.get_type())),
+		}
+	}
+
+	// explicit casts, `None` for casts into types that constants cannot hold. integers wrap around like they do at runtime
+	pub fn cast(&self, type_enum: Type) -> Option<Constant> {
+		let integer = match *self {
+			Constant::Boolean(value) => value as i64,
+			Constant::Float(number) => number as i64,
+			Constant::Float32(number) => number as i64,
+			Constant::Integer(number) => number,
+		};
+
+		match type_enum {
+			Type::Integer(0, 1) => Some(Constant::Boolean(self.as_boolean())),
+			Type::Float(0, 32) => Some(Constant::Float32(self.as_float() as f32)),
+			Type::Float(0, 64) => Some(Constant::Float(self.as_float())),
+			Type::Integer(0, bits) if bits < 64 => {
+				let shift = 64 - bits;
+				Some(Constant::Integer((integer << shift) >> shift))
+			},
+			Type::UnsignedInteger(0, bits) if bits < 64 => Some(Constant::Integer(integer & ((1 << bits) - 1))),
+			Type::Integer(0, _) | Type::UnsignedInteger(0, _) => Some(Constant::Integer(integer)),
+			_ => None,
+		}
+	}
+
+	fn fits_integer(number: i64, type_enum: Type) -> bool {
+		match type_enum {
+			Type::Integer(_, bits) if bits < 64 => -(1 << (bits - 1)) <= number && number < (1 << (bits - 1)),
+			Type::UnsignedInteger(_, bits) if bits < 64 => 0 <= number && number < (1 << bits),
+			Type::UnsignedInteger(_, _) => number >= 0,
+			_ => true,
+		}
+	}
+
+	// mirrors the math the module emits for the operation, `None` if the operation cannot be done at compile time
+	pub fn fold_operation(lhs: Constant, operation: parser::Rule, rhs: Constant) -> Option<Constant> {
+		let (lhs, rhs, is_float32) = match (lhs, rhs) {
+			(Constant::Boolean(lhs), Constant::Boolean(rhs)) => return Constant::fold_boolean_operation(lhs, operation, rhs),
+			(Constant::Float(_), _) | (_, Constant::Float(_)) => (lhs.as_float(), rhs.as_float(), false),
+			(Constant::Float32(_), _) | (_, Constant::Float32(_)) => (lhs.as_float(), rhs.as_float(), true),
+			(lhs, rhs) => { // booleans mixed with integers are promoted to integers
+				let lhs = lhs.as_integer().unwrap_or(lhs.as_boolean() as i64);
+				let rhs = rhs.as_integer().unwrap_or(rhs.as_boolean() as i64);
+				return Constant::fold_integer_operation(lhs, operation, rhs);
+			},
+		};
+
+		let result = match operation {
+			parser::Rule::addition => Constant::Float(lhs + rhs),
+			parser::Rule::division => Constant::Float(lhs / rhs),
+			parser::Rule::modulo => Constant::Float(lhs % rhs),
+			parser::Rule::multiplication => Constant::Float(lhs * rhs),
+			parser::Rule::power => Constant::Float(lhs.powf(rhs)),
+			parser::Rule::subtraction => Constant::Float(lhs - rhs),
+			parser::Rule::equals => Constant::Boolean(lhs == rhs),
+			parser::Rule::greater_than => Constant::Boolean(lhs > rhs),
+			parser::Rule::greater_than_equal_to => Constant::Boolean(lhs >= rhs),
+			parser::Rule::less_than => Constant::Boolean(lhs < rhs),
+			parser::Rule::less_than_equal_to => Constant::Boolean(lhs <= rhs),
+			parser::Rule::not_equals => Constant::Boolean(lhs != rhs),
+			_ => return None,
+		};
+
+		// math on 32 bit floats stays in 32 bits
+		match result {
+			Constant::Float(number) if is_float32 => Some(Constant::Float32(number as f32)),
+			result => Some(result),
+		}
+	}
+
+	fn fold_boolean_operation(lhs: bool, operation: parser::Rule, rhs: bool) -> Option<Constant> {
+		Some(Constant::Boolean(match operation {
+			parser::Rule::bitwise_and => lhs & rhs,
+			parser::Rule::bitwise_or => lhs | rhs,
+			parser::Rule::bitwise_xor => lhs ^ rhs,
+			parser::Rule::equals => lhs == rhs,
+			parser::Rule::not_equals => lhs != rhs,
+			_ => return None,
+		}))
+	}
+
+	fn fold_integer_operation(lhs: i64, operation: parser::Rule, rhs: i64) -> Option<Constant> {
+		Some(match operation {
+			parser::Rule::equals => Constant::Boolean(lhs == rhs),
+			parser::Rule::greater_than => Constant::Boolean(lhs > rhs),
+			parser::Rule::greater_than_equal_to => Constant::Boolean(lhs >= rhs),
+			parser::Rule::less_than => Constant::Boolean(lhs < rhs),
+			parser::Rule::less_than_equal_to => Constant::Boolean(lhs <= rhs),
+			parser::Rule::not_equals => Constant::Boolean(lhs != rhs),
+			operation => Constant::Integer(Constant::fold_integer_math(lhs, operation, rhs)?),
+		})
+	}
+
+	fn fold_integer_math(lhs: i64, operation: parser::Rule, rhs: i64) -> Option<i64> {
+		Some(match operation {
+			parser::Rule::addition => lhs.wrapping_add(rhs),
+			parser::Rule::bitwise_and => lhs & rhs,
+			parser::Rule::bitwise_or => lhs | rhs,
+			parser::Rule::bitwise_xor => lhs ^ rhs,
+			parser::Rule::division => {
+				if rhs == 0 {
+					panic!("Division by zero in constant expression");
+				}
+
+				lhs.wrapping_div(rhs)
+			},
+			parser::Rule::modulo => {
+				if rhs == 0 {
+					panic!("Division by zero in constant expression");
+				}
+
+				lhs.wrapping_rem(rhs)
+			},
+			parser::Rule::multiplication => lhs.wrapping_mul(rhs),
+			parser::Rule::power => (lhs as f64).powf(rhs as f64) as i64, // raised as doubles, like the module does
+			parser::Rule::shift_left => lhs.wrapping_shl(rhs as u32),
+			parser::Rule::shift_right => lhs.wrapping_shr(rhs as u32),
+			parser::Rule::subtraction => lhs.wrapping_sub(rhs),
+			_ => return None,
+		})
+	}
+
+	pub fn fold_unary_operation(operation: parser::Rule, value: Constant) -> Option<Constant> {
+		Some(match (operation, value) {
+			(parser::Rule::negative, Constant::Float(number)) => Constant::Float(-number),
+			(parser::Rule::negative, Constant::Float32(number)) => Constant::Float32(-number),
+			(parser::Rule::negative, Constant::Integer(number)) => Constant::Integer(number.wrapping_neg()),
+			(parser::Rule::bitwise_not, Constant::Boolean(value)) => Constant::Boolean(!value),
+			(parser::Rule::bitwise_not, Constant::Integer(number)) => Constant::Integer(!number),
+			(parser::Rule::logical_not, value) => Constant::Boolean(!value.as_boolean()),
+			_ => return None,
+		})
+	}
+}
@@ -0,0 +1,36 @@
+use pest::iterators::Pair;
+
+use crate::compiler::{ CompilationContext, Math };
+use crate::parser;
+use crate::types::convert_type_name;
+
+pub struct ConstantDeclaration;
+
+impl ConstantDeclaration {
+	// constants declared outside of a function are global, constants declared inside of a function are only visible to
+	// the rest of the function
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		let mut pairs = pair.into_inner();
+
+		let constant_name = pairs.next().unwrap().as_str();
+		let type_name = pairs.next().unwrap().as_str();
+		let constant_type = convert_type_name(context, type_name);
+
+		let Some(constant) = Math::compile_constant(context, pairs.next().unwrap()) else {
+			panic!("Value of `{}` is not a constant expression", constant_name);
+		};
+
+		let constant = constant.convert_to_type(constant_type)
+			.unwrap_or_else(|_| panic!("Value of `{}` does not fit into `{}`", constant_name, type_name));
+
+		let constants = if context.current_block.is_none() {
+			&mut context.constants
+		} else {
+			&mut context.local_constants
+		};
+
+		if constants.insert(String::from(constant_name), constant).is_some() {
+			panic!("Constant `{}` is already defined", constant_name);
+		}
+	}
+}
@@ -4,6 +4,7 @@ use pest::iterators::Pair;
 use crate::compiler::control_flow::else_statement::ElseStatement;
 use crate::compiler::{ CompilationContext, compile_pair, compile_pairs };
 use crate::parser;
+use crate::types::get_type_name;
 
 use super::ControlFlow;
 use super::else_if_statement::ElseIfStatement;
@@ -70,12 +71,15 @@ impl IfStatement {
 					continued_block
 				};
 
+				let conditional = control_flow.get_conditional_value().unwrap();
 				context.module.add_branch_if_true(
 					conditional_block,
-					control_flow.get_conditional_value().unwrap(),
+					conditional,
 					control_flow.get_start_block(),
 					jump_if_false
-				);
+				).unwrap_or_else(|_| panic!(
+					"`if` condition must be a bool, got {}; compare it against something", get_type_name(conditional.type_enum)
+				));
 			}
 
 			// add branch to each control flow's body block that jumps to a point after the control flow chain
@@ -0,0 +1,105 @@
+use ai_dsl2_compiler::Block;
+use pest::iterators::Pair;
+
+use crate::compiler::{ CompilationContext, compile_pair, compile_pairs };
+use crate::parser;
+
+pub struct MatchStatement;
+
+impl MatchStatement {
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		let mut pairs = pair.into_inner();
+
+		let value = compile_pair(context, pairs.next().unwrap()).unwrap();
+
+		// the switch goes at the end of the current block, and the block we jump to after an arm is done is split off
+		let switch_block = context.module.split_block_in_place(context.current_block.as_mut().unwrap());
+		let continued_block = context.current_block.unwrap();
+
+		let mut cases: Vec<(u64, Block)> = Vec::new();
+		let mut default_block = None;
+		let mut last_block = switch_block;
+
+		// the enum the patterns belong to, `Some(None)` if the patterns are integers
+		let mut matched_enum: Option<Option<String>> = None;
+
+		for arm in pairs {
+			let arm_block = context.module.new_block("match_arm", &context.current_function.as_ref().unwrap());
+
+			if arm.as_rule() == parser::Rule::match_default_arm {
+				default_block = Some(arm_block);
+			}
+
+			for arm_pair in arm.into_inner() {
+				if arm_pair.as_rule() != parser::Rule::match_arm_body { // interpret patterns
+					let (case, enum_name) = MatchStatement::compile_pattern(context, arm_pair);
+
+					if matched_enum.get_or_insert(enum_name.clone()) != &enum_name {
+						panic!("Match statement patterns must all be variants of the same enum, or all be integers");
+					}
+
+					if cases.iter().any(|&(other, _)| other == case) {
+						panic!("Match statement has more than one arm for the same value");
+					}
+
+					cases.push((case, arm_block));
+					continue;
+				}
+
+				// compile the arm body, and jump to the continued block once the arm is done
+				context.current_block = Some(arm_block);
+				compile_pairs(context, arm_pair.into_inner());
+
+				let function = context.module.function_table.get_function(&context.current_function.as_ref().unwrap()).unwrap();
+				if function.has_default_block_terminal(context.current_block.unwrap()) {
+					context.module.add_branch(context.current_block.unwrap(), continued_block);
+				}
+			}
+
+			context.module.move_block_after(arm_block, last_block);
+			last_block = context.current_block.unwrap();
+		}
+
+		// matches without a `_` arm have to cover every variant of an enum
+		if default_block.is_none() {
+			let Some(Some(enum_name)) = matched_enum else {
+				panic!("Match statement over integers needs a `_` arm");
+			};
+
+			let missing = context.module.get_enum_variants(&enum_name).unwrap().iter()
+				.enumerate()
+				.filter(|&(discriminant, _)| !cases.iter().any(|&(case, _)| case == discriminant as u64))
+				.map(|(_, variant)| format!("{}::{}", enum_name, variant))
+				.collect::<Vec<String>>();
+
+			if missing.len() > 0 {
+				panic!("Match statement is not exhaustive, missing {}", missing.join(", "));
+			}
+		}
+
+		context.module.add_switch(
+			switch_block, value, default_block.unwrap_or(continued_block), &cases
+		).unwrap();
+
+		context.module.move_block_after(continued_block, last_block);
+		context.current_block = Some(continued_block);
+	}
+
+	// returns the integer a pattern matches, and the enum the pattern belongs to
+	fn compile_pattern(context: &mut CompilationContext, pattern: Pair<parser::Rule>) -> (u64, Option<String>) {
+		match pattern.as_rule() {
+			parser::Rule::integer => (pattern.as_str().parse::<u64>().unwrap(), None),
+			parser::Rule::enum_variant => {
+				let mut pairs = pattern.into_inner();
+				let enum_name = pairs.next().unwrap().as_str();
+				let variant = pairs.next().unwrap().as_str();
+
+				(
+					context.module.get_enum_discriminant(enum_name, variant).unwrap(),
+					Some(enum_name.to_string()),
+				)
+			},
+			_ => unreachable!(),
+		}
+	}
+}
@@ -15,6 +15,11 @@ impl Return {
 		let pairs = pair.into_inner();
 		let value = compile_pair(context, pairs.last().unwrap()).unwrap();
 
+		// tuple literals are built from the types of their elements, so they are converted to the declared tuple type
+		let function = context.module.function_table.get_function(context.current_function.as_ref().unwrap()).unwrap();
+		let return_type = function.return_type;
+		let value = context.module.math_resolve_value(context.current_block.unwrap(), value, return_type).unwrap();
+
 		context.module.add_return(context.current_block.unwrap(), value);
 
 		Return {
@@ -0,0 +1,32 @@
+use ai_dsl2_compiler::Type;
+use pest::iterators::Pair;
+
+use crate::compiler::{ CompilationContext, PropertyAccess };
+use crate::parser;
+
+pub struct Delete;
+
+impl Delete {
+	// frees an object. only variables are tracked for use after delete, objects that are reached through properties or
+	// array elements are not
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		let target = pair.into_inner().next().unwrap();
+
+		let object = if target.as_rule() == parser::Rule::token {
+			let name = target.as_str();
+			context.check_deleted(name);
+
+			let object = context.module.get_variable(context.current_block.unwrap(), name).unwrap();
+			context.deleted_variables.insert(String::from(name));
+			object
+		} else {
+			PropertyAccess::compile(context, target)
+		};
+
+		let Type::Struct(_, _) = object.type_enum else {
+			panic!("`delete` expects an object, got {:?}", object.type_enum);
+		};
+
+		context.module.add_struct_free(context.current_block.unwrap(), object).unwrap();
+	}
+}
@@ -0,0 +1,17 @@
+use pest::iterators::Pair;
+
+use crate::compiler::CompilationContext;
+use crate::parser;
+
+pub struct EnumDeclaration;
+
+impl EnumDeclaration {
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		let mut pairs = pair.into_inner();
+
+		let enum_name = pairs.next().unwrap().as_str();
+		let variants = pairs.map(|pair| pair.as_str().to_string()).collect();
+
+		context.module.create_enum_type(enum_name, variants);
+	}
+}
@@ -0,0 +1,48 @@
+use ai_dsl2_compiler::Type;
+use pest::iterators::Pair;
+
+use crate::compiler::{ CompilationContext, Function };
+use crate::parser;
+use crate::types::convert_type_name;
+
+pub struct ExternDeclaration;
+
+impl ExternDeclaration {
+	// external functions & variables are provided by the host, and keep their names as they are written
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		if context.current_block.is_some() {
+			panic!("`extern` can only be used at the top level of a file");
+		}
+
+		let rule = pair.as_rule();
+		let mut pairs = pair.into_inner();
+		let name = pairs.next().unwrap().as_str();
+
+		if rule == parser::Rule::extern_variable {
+			let variable_type = convert_type_name(context, pairs.next().unwrap().as_str());
+			context.module.add_extern_global_variable(name, variable_type);
+			return;
+		}
+
+		let mut argument_names = Vec::new();
+		let mut argument_types = Vec::new();
+		let mut return_type = Type::Void(0);
+		let mut is_variadic = false;
+		for pair in pairs {
+			match pair.as_rule() {
+				parser::Rule::function_declaration_args => {
+					Function::compile_declaration_args(context, pair, &mut argument_names, &mut argument_types);
+				},
+				parser::Rule::variadic_argument => is_variadic = true,
+				parser::Rule::type_token => return_type = convert_type_name(context, pair.as_str()),
+				_ => unreachable!(),
+			}
+		}
+
+		if is_variadic {
+			context.module.create_variadic_extern_function(name, &argument_types, return_type);
+		} else {
+			context.module.create_extern_function(name, &argument_types, return_type);
+		}
+	}
+}
@@ -9,6 +9,15 @@ pub struct Function;
 
 impl Function {
 	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		Function::compile_function(context, pair, None);
+	}
+
+	// compiles a method from an `impl` block, `self` is passed in as the first argument
+	pub fn compile_method(context: &mut CompilationContext, pair: Pair<parser::Rule>, self_type: Type) {
+		Function::compile_function(context, pair, Some(self_type));
+	}
+
+	fn compile_function(context: &mut CompilationContext, pair: Pair<parser::Rule>, self_type: Option<Type>) {
 		let mut name = "";
 		let mut return_type = "";
 
@@ -22,33 +31,29 @@ impl Function {
 				return_type = pair.as_str();
 			} else if pair.as_rule() == parser::Rule::function_body {
 				break;
+			} else if pair.as_rule() == parser::Rule::self_argument {
+				argument_names.push(String::from("self"));
+				argument_types.push(self_type.unwrap());
 			} else if pair.as_rule() == parser::Rule::function_declaration_args { // interpret arguments
-				for argument_pair in pair.into_inner() {
-					if argument_pair.as_rule() == parser::Rule::type_token {
-						argument_types.push(convert_type_name(&context.module, argument_pair.as_str()));
-					} else if argument_pair.as_rule() == parser::Rule::token {
-						argument_names.push(String::from(argument_pair.as_str()));
-					}
-				}
+				Function::compile_declaration_args(context, pair, &mut argument_names, &mut argument_types);
 			}
 		}
 
-		context.current_function = Some(context.module.create_function(
-			name,
-			&argument_types,
-			convert_type_name(&context.module, return_type)
-		));
+		let return_type = convert_type_name(context, return_type);
+		context.current_function = Some(if let Some(self_type) = self_type {
+			context.module.create_method(self_type, name, &argument_types, return_type)
+		} else {
+			context.module.create_function(name, &argument_types, return_type)
+		});
 
 		let block = context.module.new_block(name, &context.current_function.as_ref().unwrap());
 		context.current_block = Some(block);
 
-		let mut values = Vec::new();
 		for i in 0..argument_names.len() {
 			let argument_name = &argument_names[i];
 			let argument_type = argument_types[i];
 			let function = context.module.function_table.get_function(&context.current_function.as_ref().unwrap()).unwrap();
 			let argument_value = function.get_argument(i);
-			values.push(argument_value);
 
 			context.module.add_argument(block, &argument_name, argument_type, argument_value);
 		}
@@ -57,22 +62,29 @@ impl Function {
 			pairs.clone().last().unwrap().into_inner()
 		);
 
+		let values = if has_learned_values {
+			context.module.get_airt_inputs(block, context.current_function.as_ref().unwrap())
+		} else {
+			Vec::new()
+		};
+
 		let prediction_index = if values.len() > 0 && has_learned_values { // call `airt_handle_function_call`
 			// create array of arguments
 			let array = context.module.add_immutable_array(
 				context.current_block.unwrap(),
-				Type::Float(0),
+				Type::Float(0, 64),
 				values.len()
 			);
 
 			for i in 0..values.len() {
 				let value = values[i];
-				context.module.add_store_to_array(block, array, i, value).unwrap();
+				let index = context.module.create_immediate_integer(i as i64);
+				context.module.add_store_to_array(block, array, index, value).unwrap();
 			}
 
 			// TODO cache the name
 			let allocated_name = context.module.create_global_string(
-				context.current_block.unwrap(), &context.module.transform_function_name(name)
+				context.current_block.unwrap(), &context.current_function.as_ref().unwrap().name
 			);
 
 			Some(context.module.add_function_call(
@@ -94,7 +106,32 @@ impl Function {
 			context.module.add_return_void(context.current_block.unwrap());
 		}
 
+		context.current_block = None;
 		context.current_function = None;
+		context.deleted_variables.clear();
+		context.local_constants.clear();
+	}
+
+	pub fn compile_declaration_args(
+		context: &mut CompilationContext,
+		pair: Pair<parser::Rule>,
+		argument_names: &mut Vec<String>,
+		argument_types: &mut Vec<Type>
+	) {
+		for argument_pair in pair.into_inner() {
+			if argument_pair.as_rule() == parser::Rule::type_token {
+				let argument_type = convert_type_name(context, argument_pair.as_str());
+
+				// arrays are passed by reference
+				if let Type::Array(0, _) = argument_type {
+					argument_types.push(argument_type.increment_pointer_number());
+				} else {
+					argument_types.push(argument_type);
+				}
+			} else if argument_pair.as_rule() == parser::Rule::token {
+				argument_names.push(String::from(argument_pair.as_str()));
+			}
+		}
 	}
 
 	// looks for learned values recursively, determines whether or not we process a function in the airt
@@ -1,4 +1,4 @@
-use ai_dsl2_compiler::{ FunctionKey, Value, };
+use ai_dsl2_compiler::{ FunctionKey, Type, Value, };
 use pest::iterators::Pair;
 
 use crate::compiler::CompilationContext;
@@ -12,13 +12,40 @@ impl FunctionCall {
 	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Value {
 		let mut pairs = pair.into_inner();
 
-		let function_name = context.module.transform_function_name(pairs.next().unwrap().as_str());
+		let name = pairs.next().unwrap().as_str();
 
 		let mut argument_values = Vec::new();
+		FunctionCall::compile_arguments(context, pairs.next(), &mut argument_values);
 
-		let arguments = pairs.next();
+		if let Some(value) = FunctionCall::compile_builtin(context, name, &mut argument_values) {
+			return value;
+		}
+
+		let key = context.module.resolve_function(name).unwrap();
+		context.module.add_function_call(
+			context.current_block.unwrap(), &key, &mut argument_values
+		)
+	}
+
+	// compiles a method call on `object`, the object is passed in as the method's first argument
+	pub fn compile_method(context: &mut CompilationContext, object: Value, pair: Pair<parser::Rule>) -> Value {
+		let mut pairs = pair.into_inner();
+
+		let object = context.module.resolve_value(context.current_block.unwrap(), object);
+		let key = context.module.get_method(object, pairs.next().unwrap().as_str()).unwrap();
+
+		let mut argument_values = vec![object];
+		FunctionCall::compile_arguments(context, pairs.next(), &mut argument_values);
+
+		context.module.add_function_call(
+			context.current_block.unwrap(), &key, &mut argument_values
+		)
+	}
+
+	fn compile_arguments(
+		context: &mut CompilationContext, arguments: Option<Pair<parser::Rule>>, argument_values: &mut Vec<Value>
+	) {
 		if let Some(arguments) = arguments {
-			// compile the arguments
 			for pair in arguments.into_inner() {
 				let Some(value) = compile_pair(context, pair) else {
 					unreachable!();
@@ -27,10 +54,38 @@ impl FunctionCall {
 				argument_values.push(value);
 			}
 		}
+	}
 
-		let key = FunctionKey::new(&function_name);
-		context.module.add_function_call(
-			context.current_block.unwrap(), &key, &mut argument_values
-		)
+	// builtins take precedence over functions declared in the DSL
+	fn compile_builtin(context: &mut CompilationContext, name: &str, argument_values: &mut Vec<Value>) -> Option<Value> {
+		match name {
+			"len" => { // evaluates to the size of an array at compile time
+				if argument_values.len() != 1 {
+					panic!("`len` expects exactly one argument");
+				}
+
+				let size = context.module.get_array_size(argument_values[0].type_enum)
+					.expect("`len` expects an array argument");
+
+				Some(context.module.create_immediate_integer(size as i64))
+			},
+			"print" => { // picks the runtime print function based on the argument's type
+				if argument_values.len() != 1 {
+					panic!("`print` expects exactly one argument");
+				}
+
+				let function_name = match argument_values[0].type_enum {
+					Type::CString(_) => "puts",
+					Type::Float(_, _) => "_airt_print_float",
+					Type::Integer(_, _) | Type::UnsignedInteger(_, _) => "_airt_print_int",
+					type_enum => panic!("`print` does not support {:?}", type_enum),
+				};
+
+				Some(context.module.add_function_call(
+					context.current_block.unwrap(), &FunctionKey::new(function_name), argument_values
+				))
+			},
+			_ => None,
+		}
 	}
-}
+}
\ No newline at end of file
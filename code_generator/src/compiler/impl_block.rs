@@ -0,0 +1,18 @@
+use pest::iterators::Pair;
+
+use crate::compiler::{ CompilationContext, Function };
+use crate::parser;
+use crate::types::convert_type_name;
+
+pub struct ImplBlock;
+
+impl ImplBlock {
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		let mut pairs = pair.into_inner();
+
+		let self_type = convert_type_name(context, pairs.next().unwrap().as_str());
+		for method in pairs {
+			Function::compile_method(context, method, self_type);
+		}
+	}
+}
@@ -0,0 +1,60 @@
+use std::panic::{ self, AssertUnwindSafe, };
+use std::path::{ Path, PathBuf, };
+
+use pest::iterators::{ Pair, Pairs, };
+
+use crate::compiler::{ CompilationContext, compile_pairs };
+use crate::parser::{ self, parse_program, read_program, };
+
+pub struct Import;
+
+impl Import {
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		if context.current_block.is_some() {
+			panic!("`import` can only be used at the top level of a file");
+		}
+
+		let string = pair.into_inner().next().unwrap().as_str();
+		let import_name = &string[1..string.len() - 1];
+
+		// imports are resolved relative to the file that imports them
+		let importing_file = context.file_stack.last().unwrap().clone();
+		let path = importing_file.parent().unwrap_or(Path::new("")).join(import_name);
+		let Ok(path) = path.canonicalize() else {
+			panic!("Could not find `{}` imported from `{}`", import_name, importing_file.display());
+		};
+
+		if context.file_stack.contains(&path) {
+			let cycle = context.file_stack.iter()
+				.skip_while(|file| **file != path)
+				.chain(std::iter::once(&path))
+				.map(|file| format!("`{}`", file.display()))
+				.collect::<Vec<String>>();
+
+			panic!("Import cycle detected: {}", cycle.join(" -> "));
+		}
+
+		// every file is only compiled into the module once
+		if context.imported_files.contains(&path) {
+			return;
+		}
+
+		let program = read_program(path.to_str().unwrap());
+		let pairs = parse_program(path.to_str().unwrap(), &program);
+		Import::compile_file(context, path, pairs);
+	}
+
+	// compiles the top level of a file. errors that happen while compiling the file name the file
+	pub fn compile_file(context: &mut CompilationContext, path: PathBuf, pairs: Pairs<parser::Rule>) {
+		context.imported_files.insert(path.clone());
+		context.file_stack.push(path.clone());
+
+		let result = panic::catch_unwind(AssertUnwindSafe(|| compile_pairs(context, pairs)));
+		context.file_stack.pop();
+
+		if let Err(error) = result {
+			eprintln!("error while compiling `{}`", path.display());
+			panic::resume_unwind(error);
+		}
+	}
+}
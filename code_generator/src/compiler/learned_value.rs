@@ -1,28 +1,141 @@
 use ai_dsl2_compiler::Value;
+use pest::iterators::Pair;
 
-use crate::compiler::CompilationContext;
+use crate::compiler::{ CompilationContext, Constant, Math, compile_pair };
+use crate::parser;
 
 pub struct LearnedValue;
 
 impl LearnedValue {
-	pub fn compile(context: &mut CompilationContext) -> Value {
-		let function_name = context.module.function_table.get_function(&context.current_function.as_ref().unwrap())
-			.unwrap().name.clone();
+	// compiles `#`, `#int`, `#float[0.0..100.0]`, `#[1..5]`, `#{0, 1, 2}`, etc. learned values without a type are floats,
+	// unless their bounds are integers. learned values without bounds are given the widest bounds their type allows
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Value {
+		let function = context.module.function_table.get_function(&context.current_function.as_ref().unwrap()).unwrap();
+		let function_name = function.name.clone();
+
+		// every learned value site gets its own output, numbered in the order the sites are compiled in
+		let output_index = function.learned_values.len();
+
+		// the airt needs to know which function call the learned value belongs to
+		let Some(prediction_index) = context.prediction_index else {
+			panic!("Learned values can only be used in functions that take numbers or objects as arguments");
+		};
+
+		let mut learned_type = None;
+		let mut bounds = None;
+		for pair in pair.into_inner() {
+			match pair.as_rule() {
+				parser::Rule::learned_choices => {
+					return LearnedValue::compile_choice(context, pair, &function_name, prediction_index);
+				},
+				parser::Rule::learned_type => learned_type = Some(pair.as_str()),
+				parser::Rule::learned_bounds => {
+					let mut pairs = pair.into_inner();
+					bounds = Some((
+						LearnedValue::compile_bound(context, pairs.next().unwrap()),
+						LearnedValue::compile_bound(context, pairs.next().unwrap()),
+					));
+				},
+				_ => unreachable!(),
+			}
+		}
+
+		let learned_type = learned_type.unwrap_or(match bounds {
+			Some((Constant::Integer(_), Constant::Integer(_))) => "int",
+			_ => "float",
+		});
 
 		let mut args = vec![
 			context.module.create_global_string(context.current_block.unwrap(), &function_name),
-			context.module.create_immediate_integer(0),
-			context.module.create_immediate_integer(0),
+			prediction_index,
+			context.module.create_immediate_integer(output_index as i64),
 		];
 
-		// TODO do type stuff here
+		let placeholder_evaluation = if learned_type == "int" {
+			let (low, high) = bounds.map_or((i64::MIN, i64::MAX), |(low, high)| (
+				low.as_integer().expect("Integer learned values need integer bounds"),
+				high.as_integer().expect("Integer learned values need integer bounds"),
+			));
+
+			if low > high {
+				panic!("Learned value bounds [{}..{}] are empty", low, high);
+			}
+
+			args.push(context.module.create_immediate_integer(low));
+			args.push(context.module.create_immediate_integer(high));
+
+			context.placeholder_evaluation_int.clone()
+		} else {
+			let (low, high) = bounds.map_or((f64::NEG_INFINITY, f64::INFINITY), |(low, high)| (
+				low.as_float(),
+				high.as_float(),
+			));
+
+			if low > high {
+				panic!("Learned value bounds [{}..{}] are empty", low, high);
+			}
+
+			args.push(context.module.create_immediate_float(low));
+			args.push(context.module.create_immediate_float(high));
+
+			context.placeholder_evaluation_float.clone()
+		};
+
 		let learned_value = context.module.add_function_call(
-			context.current_block.unwrap(), &context.placeholder_evaluation_float, &mut args
+			context.current_block.unwrap(), &placeholder_evaluation, &mut args
 		);
 
 		let function = context.module.function_table.get_function_mut(&context.current_function.as_ref().unwrap()).unwrap();
-		function.add_learned_value(learned_value);
+		function.add_learned_value(learned_value, 0);
 
 		return learned_value;
 	}
+
+	// bounds are evaluated at compile time, so they may use constants
+	fn compile_bound(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Constant {
+		let bound = pair.as_str().trim().to_string();
+		Math::compile_constant(context, pair)
+			.unwrap_or_else(|| panic!("Learned value bound `{}` is not a constant expression", bound))
+	}
+
+	// the airt picks one of the alternatives of a learned choice. every alternative is evaluated, and the chosen one is
+	// selected afterwards
+	fn compile_choice(
+		context: &mut CompilationContext,
+		pair: Pair<parser::Rule>,
+		function_name: &str,
+		prediction_index: Value
+	) -> Value {
+		let alternatives = pair.into_inner()
+			.map(|pair| compile_pair(context, pair).unwrap())
+			.collect::<Vec<Value>>();
+
+		// alternatives may contain learned values of their own, so the choice is numbered after them
+		let function = context.module.function_table.get_function(&context.current_function.as_ref().unwrap()).unwrap();
+		let output_index = function.learned_values.len();
+
+		let mut args = vec![
+			context.module.create_global_string(context.current_block.unwrap(), function_name),
+			prediction_index,
+			context.module.create_immediate_integer(output_index as i64),
+			context.module.create_immediate_integer(alternatives.len() as i64),
+		];
+
+		let choice = context.module.add_function_call(
+			context.current_block.unwrap(), &context.placeholder_evaluation_choice, &mut args
+		);
+
+		let function = context.module.function_table.get_function_mut(&context.current_function.as_ref().unwrap()).unwrap();
+		function.add_learned_value(choice, alternatives.len());
+
+		let block = context.current_block.unwrap();
+		let mut value = alternatives[0];
+		for (i, &alternative) in alternatives.iter().enumerate().skip(1) {
+			let index = context.module.create_immediate_integer(i as i64);
+			let is_chosen = context.module.add_equals(block, choice, index).unwrap();
+			value = context.module.add_select(block, is_chosen, alternative, value).unwrap();
+		}
+
+		return value;
+	}
 }
@@ -1,7 +1,8 @@
 use pest::iterators::Pair;
 
-use crate::compiler::{ CompilationContext, compile_pair, compile_pairs };
+use crate::compiler::{ CompilationContext, LoopContext, compile_pair, compile_pairs };
 use crate::parser;
+use crate::types::get_type_name;
 
 pub struct ForLoop;
 
@@ -26,17 +27,27 @@ impl ForLoop {
 		// compile the conditional expression
 		context.current_block = Some(conditional_block);
 		let conditional = compile_pair(context, pairs.next().unwrap()).unwrap();
-		context.module.add_branch_if_true(conditional_block, conditional, body_block, continued_block);
+		context.module.add_branch_if_true(conditional_block, conditional, body_block, continued_block)
+			.unwrap_or_else(|_| panic!(
+				"`for` condition must be a bool, got {}; compare it against something", get_type_name(conditional.type_enum)
+			));
 
 		// compile the increment expression
 		context.current_block = Some(increment_block);
 		compile_pair(context, pairs.next().unwrap());
 		context.module.add_branch(increment_block, conditional_block);
 
-		// compile the body
+		// compile the body, `continue` jumps into the increment
+		context.loop_stack.push(LoopContext {
+			break_block: continued_block,
+			continue_block: increment_block,
+		});
+
 		context.current_block = Some(body_block);
 		compile_pairs(context, pairs.next().unwrap().into_inner());
 
+		context.loop_stack.pop();
+
 		// jump into increment
 		let function = context.module.function_table.get_function(&context.current_function.as_ref().unwrap()).unwrap();
 		if function.has_default_block_terminal(context.current_block.unwrap()) {
@@ -0,0 +1,31 @@
+use ai_dsl2_compiler::Block;
+use pest::iterators::Pair;
+
+use crate::compiler::CompilationContext;
+use crate::parser;
+
+// the blocks that `break` and `continue` jump to. loops push one of these onto the compilation context before compiling
+// their body, and pop it once the body is done
+#[derive(Clone, Copy, Debug)]
+pub struct LoopContext {
+	pub break_block: Block, // the block after the loop
+	pub continue_block: Block, // the increment block for `for` loops, the conditional block for `while` loops
+}
+
+pub struct LoopControl;
+
+impl LoopControl {
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		let Some(loop_context) = context.loop_stack.last().copied() else {
+			panic!("`{}` used outside of a loop", pair.as_str());
+		};
+
+		let target = match pair.as_str() {
+			"break" => loop_context.break_block,
+			"continue" => loop_context.continue_block,
+			_ => unreachable!(),
+		};
+
+		context.module.add_branch(context.current_block.unwrap(), target);
+	}
+}
@@ -1,2 +1,3 @@
 pub mod for_loop;
+pub mod loop_control;
 pub mod while_loop;
@@ -1,13 +1,23 @@
-use ai_dsl2_compiler::{ Block, LogicOperation, MathError, Value };
+use ai_dsl2_compiler::{ Block, LogicOperation, MathError, Type, Value };
 use pest::iterators::Pair;
 
-use crate::compiler::CompilationContext;
+use crate::compiler::{ CompilationContext, Constant, };
 use crate::parser::{ self, configure_pratt };
+use crate::types::{ convert_type_name, get_type_name, };
 
-use super::{ LearnedValue, compile_pair, };
+use super::{ LearnedValue, PropertyAccess, compile_pair, };
 
 #[derive(Debug)]
 enum MathIR<'a> {
+	Cast {
+		value: Box<MathIR<'a>>,
+		type_name: &'a str,
+	},
+	Conditional {
+		condition: Box<MathIR<'a>>,
+		if_true: Box<MathIR<'a>>,
+		if_false: Box<MathIR<'a>>,
+	},
 	Constant {
 		kind: parser::Rule,
 		value: Pair<'a, parser::Rule>,
@@ -21,6 +31,12 @@ enum MathIR<'a> {
 		operation: parser::Rule,
 		rhs: Box<MathIR<'a>>,
 	},
+	Resolved { // a value that was compiled before the math tree was built
+		value: Value,
+	},
+	Tuple {
+		values: Vec<Box<MathIR<'a>>>,
+	},
 	UnaryOperation {
 		operation: parser::Rule,
 		value: Box<MathIR<'a>>,
@@ -39,34 +55,175 @@ impl Math {
 		Math::preorder(context, math_ir).unwrap().0
 	}
 
+	// compiles `lhs <operation> rhs` where the left hand side has already been compiled, used for compound assignments
+	pub fn compile_operation(
+		context: &mut CompilationContext, lhs: Value, operation: parser::Rule, rhs: Pair<parser::Rule>
+	) -> Value {
+		let lhs = Box::new(MathIR::Resolved {
+			value: lhs,
+		});
+
+		let math_ir = match operation {
+			parser::Rule::logical_and | parser::Rule::logical_or => Box::new(MathIR::LogicOperation {
+				operation,
+				values: vec![lhs, Math::_compile(rhs)],
+			}),
+			operation => Box::new(MathIR::Operation {
+				lhs,
+				operation,
+				rhs: Math::_compile(rhs),
+			}),
+		};
+
+		Math::preorder(context, math_ir).unwrap().0
+	}
+
+	// evaluates an expression at compile time, `None` if the expression depends on something only known at runtime
+	pub fn compile_constant(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Option<Constant> {
+		Math::fold(context, &Math::_compile(pair))
+	}
+
+	fn fold(context: &mut CompilationContext, node: &MathIR) -> Option<Constant> {
+		match node {
+			MathIR::Cast {
+				value,
+				type_name,
+			} => {
+				let type_enum = convert_type_name(context, type_name);
+				Math::fold(context, value)?.cast(type_enum)
+			},
+			MathIR::Conditional {
+				condition,
+				if_true,
+				if_false,
+			} => {
+				let condition = Math::fold(context, condition)?.as_boolean();
+				let (if_true, if_false) = (Math::fold(context, if_true)?, Math::fold(context, if_false)?);
+				let value = if condition { if_true } else { if_false };
+
+				// the arms share a type at runtime, so the folded value has to have that type too
+				match (if_true.get_type(), if_false.get_type()) {
+					(type1, type2) if type1 == type2 => Some(value),
+					(Type::Float(0, bits1), Type::Float(0, bits2)) => value.cast(Type::Float(0, std::cmp::max(bits1, bits2))),
+					(Type::Float(0, bits), _) | (_, Type::Float(0, bits)) => value.cast(Type::Float(0, bits)),
+					_ => None,
+				}
+			},
+			MathIR::Constant {
+				kind,
+				value,
+			} => match kind {
+				parser::Rule::boolean => Some(Constant::Boolean(value.as_str() == "true")),
+				parser::Rule::enum_variant => {
+					let mut pairs = value.clone().into_inner();
+					let name = pairs.next().unwrap().as_str();
+					context.module.get_enum_discriminant(name, pairs.next().unwrap().as_str()).ok()
+						.map(|discriminant| Constant::Integer(discriminant as i64))
+				},
+				parser::Rule::float => Some(Constant::Float(value.as_str().parse::<f64>().unwrap())),
+				parser::Rule::float32 => Some(Constant::Float32(value.as_str().trim_end_matches('f').parse::<f32>().unwrap())),
+				parser::Rule::integer => Some(Constant::Integer(value.as_str().parse::<u64>().unwrap() as i64)),
+				parser::Rule::token => context.get_constant(value.as_str()),
+				_ => None,
+			},
+			MathIR::LogicOperation {
+				operation,
+				values,
+			} => {
+				let mut result = *operation == parser::Rule::logical_and;
+				for value in values {
+					let value = Math::fold(context, value)?.as_boolean();
+					result = if *operation == parser::Rule::logical_and { result && value } else { result || value };
+				}
+
+				Some(Constant::Boolean(result))
+			},
+			MathIR::Operation {
+				lhs,
+				operation,
+				rhs,
+			} => Constant::fold_operation(Math::fold(context, lhs)?, *operation, Math::fold(context, rhs)?),
+			MathIR::UnaryOperation {
+				operation,
+				value,
+			} => Constant::fold_unary_operation(*operation, Math::fold(context, value)?),
+			MathIR::Resolved { .. } | MathIR::Tuple { .. } | MathIR::Value { .. } => None,
+		}
+	}
+
 	fn preorder(context: &mut CompilationContext, node: Box<MathIR>) -> Result<(Value, Option<Block>), MathError> {
+		// subtrees that only depend on constants are folded into a single immediate
+		if let Some(constant) = Math::fold(context, &node) {
+			return Ok((constant.to_value(&context.module), None));
+		}
+
 		match *node {
+			MathIR::Cast {
+				value,
+				type_name,
+			} => {
+				let (value, end_block) = Math::preorder(context, value)?;
+				let type_enum = convert_type_name(context, type_name);
+				let value = context.module.add_cast(context.current_block.unwrap(), value, type_enum)
+					.unwrap_or_else(|_| panic!(
+						"Cannot cast {} to {}", get_type_name(value.type_enum), get_type_name(type_enum)
+					));
+
+				Ok((value, end_block))
+			},
+			MathIR::Conditional {
+				condition,
+				if_true,
+				if_false,
+			} => {
+				let (condition, _) = Math::preorder(context, condition)?;
+				let mut conditional = context.module.new_conditional_block(context.current_block.unwrap(), condition)
+					.unwrap_or_else(|_| panic!(
+						"`if` condition must be a bool, got {}; compare it against something", get_type_name(condition.type_enum)
+					));
+
+				let arms = [(if_true, conditional.get_true_block()), (if_false, conditional.get_false_block())];
+				for (arm, block) in arms {
+					context.current_block = Some(block);
+
+					// if the arm generated blocks, then the current block is the block the arm's value was computed in
+					let (value, _) = Math::preorder(context, arm)?;
+					conditional = context.module.add_conditional_arm(conditional, context.current_block.unwrap(), value);
+				}
+
+				let (value, end_block) = context.module.commit_conditional_block(conditional)?;
+				context.current_block = Some(end_block);
+
+				Ok((value, Some(end_block)))
+			},
 			MathIR::Constant {
 				kind,
 				value,
 			} => {Ok((
 				match kind {
+					parser::Rule::boolean => context.module.create_immediate_boolean(value.as_str() == "true"),
 					parser::Rule::float => context.module.create_immediate_float(value.as_str().parse::<f64>().unwrap()),
-					parser::Rule::integer => context.module.create_immediate_integer(value.as_str().parse::<u64>().unwrap()),
-					parser::Rule::learned_value => LearnedValue::compile(context),
-					parser::Rule::property_access => {
-						let mut property_chain = value.into_inner();
-						let mut value = context.module.get_variable(
-							context.current_block.unwrap(),
-							property_chain.next().unwrap().as_str()
-						).unwrap();
-
-						for property in property_chain {
-							value = context.module.get_obj_property(
-								context.current_block.unwrap(),
-								value, // use last valeu in the chain, should always be an object
-								property.as_str(),
-							).unwrap();
-						}
-
-						value
+					parser::Rule::float32 => {
+						context.module.create_immediate_float32(value.as_str().trim_end_matches('f').parse::<f32>().unwrap())
+					},
+					parser::Rule::integer => context.module.create_immediate_integer(value.as_str().parse::<u64>().unwrap() as i64),
+					parser::Rule::enum_variant => {
+						let mut pairs = value.into_inner();
+						let name = pairs.next().unwrap().as_str();
+						context.module.get_enum_variant(name, pairs.next().unwrap().as_str())?
+					},
+					parser::Rule::learned_value => LearnedValue::compile(context, value),
+					parser::Rule::string_literal => {
+						let string = value.as_str();
+						context.module.create_global_string(context.current_block.unwrap(), &string[1..string.len() - 1])
+					},
+					parser::Rule::index_access | parser::Rule::method_call | parser::Rule::property_access => {
+						PropertyAccess::compile(context, value)
+					},
+					parser::Rule::token => {
+						context.check_deleted(value.as_str());
+						context.module.get_variable(context.current_block.unwrap(), value.as_str())?
 					},
-					parser::Rule::token => context.module.get_variable(context.current_block.unwrap(), value.as_str())?,
 					_ => unreachable!(),
 				},
 				None
@@ -127,25 +284,50 @@ impl Math {
 				let (rhs, _) = Math::preorder(context, rhs)?;
 				let current_block = context.current_block.unwrap();
 
-				return Ok((
-					match operation {
-						parser::Rule::addition => context.module.add_addition(current_block, lhs, rhs).unwrap(),
-						parser::Rule::bitwise_and => context.module.add_bitwise_and(current_block, lhs, rhs).unwrap(),
-						parser::Rule::bitwise_or => context.module.add_bitwise_or(current_block, lhs, rhs).unwrap(),
-						parser::Rule::bitwise_xor => context.module.add_bitwise_xor(current_block, lhs, rhs).unwrap(),
-						parser::Rule::division => context.module.add_division(current_block, lhs, rhs).unwrap(),
-						parser::Rule::equals => context.module.add_equals(current_block, lhs, rhs).unwrap(),
-						parser::Rule::greater_than => context.module.add_greater_than(current_block, lhs, rhs).unwrap(),
-						parser::Rule::greater_than_equal_to => context.module.add_greater_than_equal_to(current_block, lhs, rhs).unwrap(),
-						parser::Rule::less_than => context.module.add_less_than(current_block, lhs, rhs).unwrap(),
-						parser::Rule::less_than_equal_to => context.module.add_less_than_equal_to(current_block, lhs, rhs).unwrap(),
-						parser::Rule::multiplication => context.module.add_multiplication(current_block, lhs, rhs).unwrap(),
-						parser::Rule::not_equals => context.module.add_not_equals(current_block, lhs, rhs).unwrap(),
-						parser::Rule::subtraction => context.module.add_subtraction(current_block, lhs, rhs).unwrap(),
-						rule => todo!("{:?} not implemented", rule),
-					},
-					None
-				));
+				let result = match operation {
+					parser::Rule::addition => context.module.add_addition(current_block, lhs, rhs),
+					parser::Rule::bitwise_and => context.module.add_bitwise_and(current_block, lhs, rhs),
+					parser::Rule::bitwise_or => context.module.add_bitwise_or(current_block, lhs, rhs),
+					parser::Rule::bitwise_xor => context.module.add_bitwise_xor(current_block, lhs, rhs),
+					parser::Rule::division => context.module.add_division(current_block, lhs, rhs),
+					parser::Rule::equals => context.module.add_equals(current_block, lhs, rhs),
+					parser::Rule::greater_than => context.module.add_greater_than(current_block, lhs, rhs),
+					parser::Rule::greater_than_equal_to => context.module.add_greater_than_equal_to(current_block, lhs, rhs),
+					parser::Rule::less_than => context.module.add_less_than(current_block, lhs, rhs),
+					parser::Rule::less_than_equal_to => context.module.add_less_than_equal_to(current_block, lhs, rhs),
+					parser::Rule::modulo => context.module.add_remainder(current_block, lhs, rhs),
+					parser::Rule::multiplication => context.module.add_multiplication(current_block, lhs, rhs),
+					parser::Rule::not_equals => context.module.add_not_equals(current_block, lhs, rhs),
+					parser::Rule::power => context.module.add_power(current_block, lhs, rhs),
+					parser::Rule::shift_left => context.module.add_shift_left(current_block, lhs, rhs),
+					parser::Rule::shift_right => context.module.add_shift_right(current_block, lhs, rhs),
+					parser::Rule::subtraction => context.module.add_subtraction(current_block, lhs, rhs),
+					rule => todo!("{:?} not implemented", rule),
+				};
+
+				if let Err(MathError::IncompatibleTypes(type1, type2)) = result {
+					panic!(
+						"Cannot mix {} and {}, convert one of them with `as`", get_type_name(type1), get_type_name(type2)
+					);
+				}
+
+				Ok((result.unwrap(), None))
+			},
+			MathIR::Resolved {
+				value,
+			} => Ok((value, None)),
+			MathIR::Tuple {
+				values,
+			} => {
+				let mut tuple_values = Vec::new();
+				let mut end_block = None;
+				for value in values {
+					let (value, value_end_block) = Math::preorder(context, value)?;
+					tuple_values.push(value);
+					end_block = value_end_block.or(end_block);
+				}
+
+				Ok((context.module.add_tuple(context.current_block.unwrap(), &tuple_values), end_block))
 			},
 			MathIR::UnaryOperation {
 				operation,
@@ -175,6 +357,26 @@ impl Math {
 		}
 	}
 
+	// `else if` chains are nested conditionals
+	fn _compile_conditional(pair: Pair<parser::Rule>) -> Box<MathIR> {
+		let mut pairs = pair.into_inner();
+		let condition = Math::_compile(pairs.next().unwrap());
+		let if_true = Math::_compile(pairs.next().unwrap());
+
+		let if_false = pairs.next().unwrap();
+		let if_false = if if_false.as_rule() == parser::Rule::conditional_expression {
+			Math::_compile_conditional(if_false)
+		} else {
+			Math::_compile(if_false)
+		};
+
+		Box::new(MathIR::Conditional {
+			condition,
+			if_true,
+			if_false,
+		})
+	}
+
 	// vectorize operations into intermediate representation that we parse later
 	fn _compile(pair: Pair<parser::Rule>) -> Box<MathIR> {
 		let value = configure_pratt()
@@ -182,7 +384,11 @@ impl Math {
 				parser::Rule::function_call => Box::new(MathIR::Value {
 					pair: primary,
 				}),
+				parser::Rule::conditional_expression => Math::_compile_conditional(primary),
 				parser::Rule::math => Math::_compile(primary),
+				parser::Rule::tuple_literal => Box::new(MathIR::Tuple {
+					values: primary.into_inner().map(|pair| Math::_compile(pair)).collect(),
+				}),
 				kind => Box::new(MathIR::Constant {
 					kind,
 					value: primary,
@@ -193,6 +399,11 @@ impl Math {
 					value,
 				})
 			)
+			.map_postfix(|value, op| Box::new(MathIR::Cast {
+					value,
+					type_name: op.into_inner().next().unwrap().as_str(),
+				})
+			)
 			.map_infix(|mut lhs, op, rhs| match op.as_rule() {
 				parser::Rule::logical_and | parser::Rule::logical_or => {
 					if let MathIR::LogicOperation { operation, values } = lhs.as_mut() {
@@ -1,25 +1,43 @@
 pub mod compile;
+pub mod constant;
+pub mod constant_declaration;
 pub mod control_flow;
+pub mod delete;
+pub mod enum_declaration;
+pub mod extern_declaration;
 pub mod function;
 pub mod function_call;
+pub mod impl_block;
+pub mod import;
 pub mod learned_value;
 pub mod loops;
 pub mod math;
 pub mod new_struct;
+pub mod property_access;
 pub mod struct_declaration;
 pub mod variable_assignment;
 pub mod variable_declaration;
 
 pub use compile::{ CompilationContext, compile_pair, compile_pairs, };
+pub use constant::Constant;
+pub use constant_declaration::ConstantDeclaration;
 pub use control_flow::if_statement::IfStatement;
+pub use control_flow::match_statement::MatchStatement;
 pub use control_flow::return_statement::Return;
 pub use control_flow::void_return_statement::VoidReturn;
+pub use delete::Delete;
+pub use enum_declaration::EnumDeclaration;
+pub use extern_declaration::ExternDeclaration;
 pub use learned_value::LearnedValue;
 pub use loops::for_loop::ForLoop;
+pub use loops::loop_control::{ LoopContext, LoopControl, };
 pub use function::Function;
 pub use function_call::FunctionCall;
+pub use impl_block::ImplBlock;
+pub use import::Import;
 pub use math::Math;
 pub use new_struct::NewStruct;
+pub use property_access::PropertyAccess;
 pub use struct_declaration::StructDeclaration;
 pub use variable_assignment::VariableAssignment;
 pub use variable_declaration::VariableDeclaration;
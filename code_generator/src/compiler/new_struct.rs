@@ -1,7 +1,7 @@
-use ai_dsl2_compiler::Value;
+use ai_dsl2_compiler::{ Type, Value, };
 use pest::iterators::Pair;
 
-use crate::compiler::CompilationContext;
+use crate::compiler::{ CompilationContext, compile_pair };
 use crate::parser;
 
 pub struct NewStruct;
@@ -12,9 +12,41 @@ impl NewStruct {
 
 		let struct_name = pairs.next().unwrap().as_str();
 
-		context.module.add_struct_malloc(
+		let object = context.module.add_struct_malloc(
 			context.current_block.unwrap(),
 			struct_name,
-		)
+		);
+
+		let Type::Struct(_, type_index) = object.type_enum else {
+			unreachable!();
+		};
+
+		// the initializers are stored after the defaults, so they overwrite them
+		let mut initialized = Vec::new();
+		for pair in pairs {
+			let mut field_pairs = pair.into_inner();
+
+			let property = field_pairs.next().unwrap().as_str();
+			if context.module.get_property_type(type_index, property).is_none() {
+				panic!("Struct `{}` does not have a field named `{}`", struct_name, property);
+			} else if initialized.contains(&property) {
+				panic!("Field `{}` of `{}` is initialized more than once", property, struct_name);
+			}
+
+			let value = compile_pair(context, field_pairs.next().unwrap()).unwrap();
+			context.module.add_store_to_obj(context.current_block.unwrap(), object, property, value).unwrap();
+
+			initialized.push(property);
+		}
+
+		for property in context.module.get_properties(type_index) {
+			if initialized.contains(&property.as_str()) || context.module.has_property_default(type_index, &property) {
+				continue;
+			}
+
+			panic!("Field `{}` of `{}` is not initialized and has no default value", property, struct_name);
+		}
+
+		object
 	}
 }
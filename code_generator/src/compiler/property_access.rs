@@ -0,0 +1,72 @@
+use ai_dsl2_compiler::Value;
+use pest::iterators::Pair;
+
+use crate::compiler::{ CompilationContext, FunctionCall, compile_pair };
+use crate::parser;
+
+pub struct PropertyAccess;
+
+impl PropertyAccess {
+	// compiles a read of a property/index chain, like `car.speed`, `xs[i]`, `car.engine.rpm`, `cars[i].speed`,
+	// `get_car().speed` or `car.update()`
+	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Value {
+		let chain = PropertyAccess::flatten(pair);
+		PropertyAccess::compile_chain(context, &chain, true)
+	}
+
+	// compiles the location of an array element so it can be assigned to
+	pub fn compile_location(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Value {
+		let chain = PropertyAccess::flatten(pair);
+		PropertyAccess::compile_chain(context, &chain, false)
+	}
+
+	// walks a flattened chain. the first link is a variable name or a function call, every link after that is either a
+	// property name, a method call or an array index. array indices in the middle of the chain produce element locations, while the
+	// last array index is only loaded if `load_last` is set
+	pub fn compile_chain(
+		context: &mut CompilationContext, chain: &[Pair<parser::Rule>], load_last: bool
+	) -> Value {
+		let mut value = if chain[0].as_rule() == parser::Rule::function_call {
+			FunctionCall::compile(context, chain[0].clone())
+		} else {
+			context.check_deleted(chain[0].as_str());
+			context.module.get_variable(
+				context.current_block.unwrap(),
+				chain[0].as_str()
+			).unwrap()
+		};
+
+		for (i, link) in chain.iter().enumerate().skip(1) {
+			value = match link.as_rule() {
+				parser::Rule::array_index => {
+					let index = compile_pair(context, link.clone().into_inner().next().unwrap()).unwrap();
+
+					if load_last && i == chain.len() - 1 {
+						context.module.get_array_element(context.current_block.unwrap(), value, index).unwrap()
+					} else {
+						context.module.add_array_element(context.current_block.unwrap(), value, index).unwrap()
+					}
+				},
+				parser::Rule::function_call => FunctionCall::compile_method(context, value, link.clone()),
+				parser::Rule::token => context.module.get_obj_property(
+					context.current_block.unwrap(),
+					value, // use last value in the chain, should always be an object
+					link.as_str(),
+				).unwrap(),
+				_ => unreachable!(),
+			};
+		}
+
+		value
+	}
+
+	// turns nested `property_access`/`index_access` pairs into a flat list of links
+	pub fn flatten(pair: Pair<parser::Rule>) -> Vec<Pair<parser::Rule>> {
+		match pair.as_rule() {
+			parser::Rule::index_access | parser::Rule::method_call | parser::Rule::property_access => {
+				pair.into_inner().flat_map(|pair| PropertyAccess::flatten(pair)).collect()
+			},
+			_ => vec![pair],
+		}
+	}
+}
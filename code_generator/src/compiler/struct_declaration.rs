@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use pest::iterators::Pair;
 
-use crate::compiler::CompilationContext;
+use crate::compiler::{ CompilationContext, Math };
 use crate::parser;
-use crate::types::convert_type_name;
+use crate::types::{ convert_type_name, get_type_name, };
 
 pub struct StructDeclaration;
 
@@ -11,15 +13,37 @@ impl StructDeclaration {
 		let mut pairs = pair.into_inner();
 
 		let struct_name = pairs.next().unwrap().as_str();
-		let struct_fields = pairs.map(|pair| {
+
+		let mut struct_fields = Vec::new();
+		let mut struct_defaults = HashMap::new();
+		for pair in pairs {
 			let mut field_pairs = pair.into_inner();
 
-			(
-				field_pairs.next().unwrap().as_str().to_string(), // property name
-				convert_type_name(&context.module, field_pairs.next().unwrap().as_str()), // property type
-			)
-		}).collect();
+			let property_name = field_pairs.next().unwrap().as_str().to_string();
+			let property_type = convert_type_name(context, field_pairs.next().unwrap().as_str());
+
+			// default values have to be known at compile time, since they are stored by every `new`. arrays take a
+			// default for their elements
+			if let Some(default) = field_pairs.next() {
+				let Some(constant) = Math::compile_constant(context, default) else {
+					panic!("Default value of `{}.{}` is not a constant expression", struct_name, property_name);
+				};
+
+				let mut value_type = property_type;
+				while let Ok(element_type) = context.module.get_array_element_type(value_type) {
+					value_type = element_type;
+				}
+
+				let constant = constant.convert_to_type(value_type).unwrap_or_else(|_| panic!(
+					"Value of `{}.{}` does not fit into `{}`", struct_name, property_name, get_type_name(value_type)
+				));
+
+				struct_defaults.insert(property_name.clone(), constant.to_value(&context.module));
+			}
+
+			struct_fields.push((property_name, property_type));
+		}
 
-		context.module.create_struct_type(struct_name, struct_fields);
+		context.module.create_struct_type(struct_name, struct_fields, struct_defaults);
 	}
 }
@@ -1,55 +1,143 @@
-use ai_dsl2_compiler::Value;
+use ai_dsl2_compiler::{ MathError, Value, };
 use pest::iterators::Pair;
 
-use crate::compiler::{ CompilationContext, compile_pair };
+use crate::compiler::{ CompilationContext, Math, PropertyAccess, compile_pair };
 use crate::parser;
 
 pub struct VariableAssignment;
 
 impl VariableAssignment {
 	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Value {
-		let property_assignment = pair.as_rule() == parser::Rule::property_assignment;
+		let rule = pair.as_rule();
 		let mut pairs = pair.into_inner();
 
-		if property_assignment {
-			let mut property_chain = pairs.next().unwrap().into_inner();
-			let mut value = context.module.get_variable(
+		if rule == parser::Rule::property_assignment {
+			let mut property_chain = PropertyAccess::flatten(pairs.next().unwrap());
+			let property_name = property_chain.pop().unwrap();
+			let operation = VariableAssignment::get_operation(pairs.next().unwrap());
+
+			let object = PropertyAccess::compile_chain(context, &property_chain, false);
+
+			let assignment_value = if let Some(operation) = operation {
+				let current_value = context.module.get_obj_property(
+					context.current_block.unwrap(),
+					object,
+					property_name.as_str(),
+				).unwrap();
+
+				Math::compile_operation(context, current_value, operation, pairs.next().unwrap())
+			} else {
+				compile_pair(context, pairs.next().unwrap()).unwrap()
+			};
+
+			context.module.add_store_to_obj(
 				context.current_block.unwrap(),
-				property_chain.next().unwrap().as_str()
-			).unwrap();
-			let assignment_value = compile_pair(context, pairs.last().unwrap()).unwrap();
-
-			let property_chain = property_chain.collect::<Vec<Pair<parser::Rule>>>();
-			let length = property_chain.len();
-			for i in 0..length {
-				let property_name = property_chain.get(i).unwrap().as_str();
-				if i == length - 1 {
-					value = context.module.add_store_to_obj(
-						context.current_block.unwrap(),
-						value, // use last value in the chain, should always be an object
-						property_name,
-						assignment_value,
-					).unwrap();
+				object, // use last value in the chain, should always be an object
+				property_name.as_str(),
+				assignment_value,
+			).unwrap()
+		} else {
+			let mut variable_name = None;
+			let location = if rule == parser::Rule::index_assignment {
+				PropertyAccess::compile_location(context, pairs.next().unwrap())
+			} else {
+				variable_name = Some(pairs.next().unwrap().as_str());
+				VariableAssignment::get_variable_location(context, variable_name.unwrap())
+			};
+
+			let operation = VariableAssignment::get_operation(pairs.next().unwrap());
+
+			// a variable that is assigned a new object is no longer deleted
+			if let Some(variable_name) = variable_name {
+				if operation.is_some() {
+					context.check_deleted(variable_name);
 				} else {
-					value = context.module.get_obj_property( // TODO this hasn't been tested b/c nested objects aren't even implemented yet
-						context.current_block.unwrap(),
-						value, // use last valeu in the chain, should always be an object
-						property_name,
-					).unwrap();
+					context.deleted_variables.remove(variable_name);
 				}
 			}
 
-			value
-		} else {
-			let variable_name = pairs.next().unwrap().as_str();
-			let variable = context.module.get_variable(context.current_block.unwrap(), variable_name).unwrap();
+			let value = if let Some(operation) = operation {
+				let current_value = context.module.resolve_value(context.current_block.unwrap(), location);
+				Math::compile_operation(context, current_value, operation, pairs.next().unwrap())
+			} else {
+				compile_pair(context, pairs.next().unwrap()).unwrap()
+			};
 
-			let value = compile_pair(context, pairs.last().unwrap()).unwrap();
 			context.module.add_store(
 				context.current_block.unwrap(),
-				variable,
+				location,
 				value
 			).unwrap()
 		}
 	}
-}
\ No newline at end of file
+
+	// compiles `x++` and `x--`. the one that is added or subtracted takes the type of the value being changed
+	pub fn compile_increment(context: &mut CompilationContext, pair: Pair<parser::Rule>) -> Value {
+		let mut pairs = pair.into_inner();
+		let target = pairs.next().unwrap();
+		let is_increment = pairs.next().unwrap().as_rule() == parser::Rule::increment;
+
+		let block = context.current_block.unwrap();
+		let (object, property_name, current_value) = match target.as_rule() {
+			parser::Rule::property_access => {
+				let mut property_chain = PropertyAccess::flatten(target);
+				let property_name = property_chain.pop().unwrap();
+				let object = PropertyAccess::compile_chain(context, &property_chain, false);
+				let current_value = context.module.get_obj_property(block, object, property_name.as_str()).unwrap();
+				(Some(object), Some(property_name), current_value)
+			},
+			parser::Rule::index_access => (None, None, PropertyAccess::compile_location(context, target)),
+			_ => {
+				context.check_deleted(target.as_str());
+				(None, None, VariableAssignment::get_variable_location(context, target.as_str()))
+			},
+		};
+
+		let value = context.module.resolve_value(block, current_value);
+		let one = context.module.add_cast(block, context.module.create_immediate_integer(1), value.type_enum).unwrap();
+
+		let new_value = if is_increment {
+			context.module.add_addition(block, value, one).unwrap()
+		} else {
+			context.module.add_subtraction(block, value, one).unwrap()
+		};
+
+		if let (Some(object), Some(property_name)) = (object, property_name) {
+			context.module.add_store_to_obj(block, object, property_name.as_str(), new_value).unwrap()
+		} else {
+			context.module.add_store(block, current_value, new_value).unwrap()
+		}
+	}
+
+	// the location of a variable that is assigned to, immutable variables cannot be assigned to
+	fn get_variable_location(context: &mut CompilationContext, name: &str) -> Value {
+		context.module.get_variable_location(context.current_block.unwrap(), name)
+			.unwrap_or_else(|error| match error {
+				MathError::ImmutableVariable(_) => {
+					panic!("Cannot assign to immutable variable `{}`, declare it with `let mut`", name)
+				},
+				error => panic!("{:?}", error),
+			})
+	}
+
+	// translates a compound assignment operator into the math operation it applies, `None` for plain assignment
+	fn get_operation(pair: Pair<parser::Rule>) -> Option<parser::Rule> {
+		match pair.as_str() {
+			"=" => None,
+			"+=" => Some(parser::Rule::addition),
+			"-=" => Some(parser::Rule::subtraction),
+			"*=" => Some(parser::Rule::multiplication),
+			"/=" => Some(parser::Rule::division),
+			"%=" => Some(parser::Rule::modulo),
+			"**=" => Some(parser::Rule::power),
+			"<<=" => Some(parser::Rule::shift_left),
+			">>=" => Some(parser::Rule::shift_right),
+			"&&=" => Some(parser::Rule::logical_and),
+			"||=" => Some(parser::Rule::logical_or),
+			"&=" => Some(parser::Rule::bitwise_and),
+			"|=" => Some(parser::Rule::bitwise_or),
+			"^=" => Some(parser::Rule::bitwise_xor),
+			operator => unreachable!("unknown assignment operator {}", operator),
+		}
+	}
+}
@@ -1,7 +1,7 @@
-use ai_dsl2_compiler::Type;
+use ai_dsl2_compiler::{ Type, Value, };
 use pest::iterators::Pair;
 
-use crate::compiler::{ CompilationContext, compile_pair };
+use crate::compiler::{ CompilationContext, Math, compile_pair };
 use crate::parser;
 use crate::types::convert_type_name;
 
@@ -17,37 +17,137 @@ impl VariableDeclaration {
 	pub fn compile(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
 		let mut variable_name = "";
 		let mut variable_type = "";
+		let mut is_mutable = false;
 
-		if context.current_block.is_none() { // compile a global variable declaration
-			let pairs = pair.into_inner();
-			for pair in pairs.clone() {
-				if pair.as_rule() == parser::Rule::token {
-					variable_name = pair.as_str();
-				} else if pair.as_rule() == parser::Rule::type_token {
-					variable_type = pair.as_str();
-				}
+		let pairs = pair.into_inner();
+		for pair in pairs.clone() {
+			if pair.as_rule() == parser::Rule::token {
+				variable_name = pair.as_str();
+			} else if pair.as_rule() == parser::Rule::type_token {
+				variable_type = pair.as_str();
+			} else if pair.as_rule() == parser::Rule::mutable {
+				is_mutable = true;
 			}
+		}
 
-			context.module.add_global_variable(
-				variable_name, convert_type_name(&context.module, variable_type)
-			);
-		} else { // compile a local variable declaration
-			let pairs = pair.into_inner();
-			for pair in pairs.clone() {
-				if pair.as_rule() == parser::Rule::token {
-					variable_name = pair.as_str();
-				} else if pair.as_rule() == parser::Rule::type_token {
-					variable_type = pair.as_str();
-				}
-			}
+		let variable_type = convert_type_name(context, variable_type);
+
+		// the initializer, if there is one
+		let last = pairs.last().unwrap();
+		let initializer = if last.as_rule() != parser::Rule::type_token {
+			Some(last)
+		} else {
+			None
+		};
+
+		if !is_mutable && initializer.is_none() {
+			panic!("Immutable variable `{}` needs an initializer, declare it with `let mut` instead", variable_name);
+		}
+
+		if context.current_block.is_none() { // compile a global variable declaration
+			let variable = if is_mutable {
+				context.module.add_global_variable(variable_name, variable_type)
+			} else {
+				context.module.add_immutable_global_variable(variable_name, variable_type)
+			};
 
-			let variable_type = convert_type_name(&context.module, variable_type);
+			if let Some(initializer) = initializer {
+				VariableDeclaration::compile_global_initializer(context, variable, variable_type, initializer);
+			} else {
+				context.module.add_global_reset(variable);
+			}
+		} else if is_mutable { // compile a mutable local variable declaration
 			let variable = context.module.add_mutable_variable(
 				context.current_block.unwrap(), variable_name, variable_type
 			);
 
-			let value = compile_pair(context, pairs.last().unwrap()).unwrap();
-			context.module.add_store(context.current_block.unwrap(), variable, value).unwrap();
+			context.deleted_variables.remove(variable_name);
+
+			if let Some(initializer) = initializer {
+				let value = compile_pair(context, initializer).unwrap();
+				context.module.add_store(context.current_block.unwrap(), variable, value).unwrap();
+			}
+		} else { // immutable local variables are bound to the value of their initializer
+			let value = compile_pair(context, initializer.unwrap()).unwrap();
+			context.module.add_immutable_variable(
+				context.current_block.unwrap(), variable_name, variable_type, value
+			).unwrap();
+
+			context.deleted_variables.remove(variable_name);
+		}
+	}
+
+	// destructures a tuple into one variable per element, `let (position, speed) = step(...);`. the variables take the
+	// types of the elements
+	pub fn compile_tuple(context: &mut CompilationContext, pair: Pair<parser::Rule>) {
+		if context.current_block.is_none() {
+			panic!("Tuples can only be destructured inside of a function");
+		}
+
+		let mut pairs = pair.into_inner().collect::<Vec<_>>();
+		let is_mutable = pairs[0].as_rule() == parser::Rule::mutable;
+		if is_mutable {
+			pairs.remove(0);
 		}
+
+		let value = compile_pair(context, pairs.pop().unwrap()).unwrap();
+
+		let block = context.current_block.unwrap();
+		let element_types = context.module.get_tuple_element_types(value.type_enum.zero_pointer_number())
+			.unwrap_or_else(|_| panic!("Cannot destructure {:?}, it is not a tuple", value.type_enum));
+
+		if element_types.len() != pairs.len() {
+			panic!("Cannot destructure a tuple of {} elements into {} variables", element_types.len(), pairs.len());
+		}
+
+		for (index, pair) in pairs.iter().enumerate() {
+			let variable_name = pair.as_str();
+			let element = context.module.get_tuple_element(block, value, index).unwrap();
+			if is_mutable {
+				let variable = context.module.add_mutable_variable(block, variable_name, element_types[index]);
+				context.module.add_store(block, variable, element).unwrap();
+			} else {
+				context.module.add_immutable_variable(block, variable_name, element_types[index], element).unwrap();
+			}
+
+			context.deleted_variables.remove(variable_name);
+		}
+	}
+
+	// constant initializers are baked into the global, everything else is compiled into the global constructor. the
+	// constructor runs before every episode, so it stores constant initializers as well
+	fn compile_global_initializer(
+		context: &mut CompilationContext, variable: Value, variable_type: Type, pair: Pair<parser::Rule>
+	) {
+		if pair.as_rule() == parser::Rule::math {
+			let constant = Math::compile_constant(context, pair.clone())
+				.and_then(|constant| constant.convert_to_type(variable_type).ok());
+
+			if let Some(constant) = constant {
+				let value = constant.to_value(&context.module);
+				if context.module.set_global_initializer(variable, value).is_ok() {
+					context.module.add_global_reset(variable);
+					return;
+				}
+			}
+		}
+
+		let (function, block) = context.module.get_global_constructor();
+		context.current_function = Some(function);
+		context.current_block = Some(block);
+
+		// the object from the last episode is freed before it is replaced, unless the arena owns it
+		if let Type::Struct(_, _) = variable_type {
+			context.module.add_struct_free(block, variable).unwrap();
+		}
+
+		let value = compile_pair(context, pair).unwrap();
+		context.module.add_store(context.current_block.unwrap(), variable, value).unwrap();
+
+		context.module.set_global_constructor_block(context.current_block.unwrap());
+		context.current_block = None;
+		context.current_function = None;
+		context.deleted_variables.clear();
+		context.local_constants.clear();
 	}
 }
\ No newline at end of file
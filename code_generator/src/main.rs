@@ -3,11 +3,34 @@ mod parser;
 mod types;
 
 fn main() {
+	// `--header <file>` also writes a C header that describes the module, `--arena` allocates objects from an arena that
+	// is reset between episodes
+	let mut input_filename = String::from("test.ai");
+	let mut header_filename = None;
+	let mut use_arena = false;
+
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--arena" => use_arena = true,
+			"--header" => header_filename = Some(args.next().expect("`--header` expects a file name")),
+			_ => input_filename = arg,
+		}
+	}
+
 	let mut state = parser::ParserState::default();
-	let mut context = compiler::CompilationContext::new("test.ai", &mut state);
+	let mut context = compiler::CompilationContext::new(&input_filename, &mut state);
+	if use_arena {
+		context.module.enable_arena();
+	}
 
 	let pairs = context.parser.pairs.clone();
-	compiler::compile_pairs(&mut context, pairs);
+	let path = std::path::Path::new(&input_filename).canonicalize().unwrap();
+	compiler::Import::compile_file(&mut context, path, pairs);
+
+	if let Some(header_filename) = header_filename {
+		context.module.write_c_header(&header_filename);
+	}
 
 	context.module.write_bitcode("main.bc");
 }
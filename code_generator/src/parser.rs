@@ -11,9 +11,12 @@ pub fn configure_pratt() -> PrattParser<Rule> {
 		.op(Op::infix(Rule::equals, Assoc::Left) | Op::infix(Rule::not_equals, Assoc::Left))
 		.op(Op::infix(Rule::less_than_equal_to, Assoc::Left) | Op::infix(Rule::greater_than_equal_to, Assoc::Left))
 		.op(Op::infix(Rule::less_than, Assoc::Left) | Op::infix(Rule::greater_than, Assoc::Left))
+		.op(Op::infix(Rule::shift_left, Assoc::Left) | Op::infix(Rule::shift_right, Assoc::Left))
 		.op(Op::infix(Rule::addition, Assoc::Left) | Op::infix(Rule::subtraction, Assoc::Left))
-		.op(Op::infix(Rule::multiplication, Assoc::Left) | Op::infix(Rule::division, Assoc::Left))
+		.op(Op::infix(Rule::multiplication, Assoc::Left) | Op::infix(Rule::division, Assoc::Left) | Op::infix(Rule::modulo, Assoc::Left))
+		.op(Op::postfix(Rule::cast))
 		.op(Op::prefix(Rule::negative) | Op::prefix(Rule::logical_not) | Op::prefix(Rule::bitwise_not))
+		.op(Op::infix(Rule::power, Assoc::Right)) // `-x ** 2` is `-(x ** 2)`
 }
 
 #[derive(Default)]
@@ -24,14 +27,29 @@ pub struct ParserState<'a> {
 
 impl ParserState<'_> {
 	pub fn parse_file(&mut self, file_name: &str) -> DSLParser<'_> {
-		self.programs.push(std::fs::read_to_string(file_name).unwrap());
+		self.programs.push(read_program(file_name));
 		DSLParser {
-			pairs: DSLParser::parse(Rule::program, &self.programs.iter().last().unwrap()).unwrap(),
+			pairs: parse_program(file_name, &self.programs.iter().last().unwrap()),
 			pratt: configure_pratt(),
 		}
 	}
 }
 
+pub fn read_program(file_name: &str) -> String {
+	match std::fs::read_to_string(file_name) {
+		Ok(program) => program,
+		Err(error) => panic!("Could not read `{}`: {}", file_name, error),
+	}
+}
+
+// parse errors name the file they come from
+pub fn parse_program<'a>(file_name: &str, program: &'a str) -> Pairs<'a, Rule> {
+	match DSLParser::parse(Rule::program, program) {
+		Ok(pairs) => pairs,
+		Err(error) => panic!("{}", error.with_path(file_name)),
+	}
+}
+
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 pub struct DSLParser<'a> {
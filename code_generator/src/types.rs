@@ -1,12 +1,83 @@
-use ai_dsl2_compiler::{ Module, Type, };
+use ai_dsl2_compiler::Type;
+
+use crate::compiler::CompilationContext;
+
+pub fn convert_type_name(context: &mut CompilationContext, type_name: &str) -> Type {
+	// handling tuples, `(float, int)` is a tuple of a float and an int
+	if let Some(element_type_names) = type_name.strip_prefix('(').and_then(|name| name.strip_suffix(')')) {
+		let element_types = split_tuple_type_name(element_type_names).into_iter()
+			.map(|element_type_name| convert_type_name(context, element_type_name))
+			.collect();
+
+		return context.module.get_tuple_type(element_types);
+	}
+
+	// handling arrays, `float[16]` is an array of 16 floats. the size may also be an integer constant
+	if let Some((element_type_name, size)) = type_name.strip_suffix(']').and_then(|name| name.rsplit_once('[')) {
+		let size = size.parse::<usize>().ok()
+			.or_else(|| context.get_constant(size).and_then(|constant| constant.as_integer()).map(|size| size as usize))
+			.unwrap_or_else(|| panic!("Array size `{}` is not an integer constant", size));
+
+		let element_type = convert_type_name(context, element_type_name);
+		return context.module.get_array_type(element_type, size);
+	}
+
+	let module = &mut context.module;
 
-pub fn convert_type_name(module: &Module, type_name: &str) -> Type {
 	match type_name {
-		"float" => Type::Float(0),
-		"int" => Type::Integer(0, 64),
+		"bool" => Type::Integer(0, 1),
+		"float" | "float64" => Type::Float(0, 64),
+		"float32" => Type::Float(0, 32),
+		"int" | "i64" => Type::Integer(0, 64),
+		"i8" => Type::Integer(0, 8),
+		"i16" => Type::Integer(0, 16),
+		"i32" => Type::Integer(0, 32),
+		"u8" => Type::UnsignedInteger(0, 8),
+		"u16" => Type::UnsignedInteger(0, 16),
+		"u32" => Type::UnsignedInteger(0, 32),
+		"u64" => Type::UnsignedInteger(0, 64),
 		"string" => Type::CString(0),
+		name if module.get_enum_variants(name).is_some() => Type::Integer(0, 64), // enums are lowered to integers
 		name => { // handling structs
 			Type::Struct(1, module.lookup_struct_type_index(name))
 		}
 	}
-}
\ No newline at end of file
+}
+
+// the name a type is written as, used in diagnostics
+pub fn get_type_name(type_enum: Type) -> String {
+	match type_enum.zero_pointer_number() {
+		Type::Array(_, _) => String::from("array"),
+		Type::CString(_) => String::from("string"),
+		Type::Float(_, 32) => String::from("float32"),
+		Type::Float(_, _) => String::from("float"),
+		Type::Integer(_, 1) => String::from("bool"),
+		Type::Integer(_, 64) => String::from("int"),
+		Type::Integer(_, bits) => format!("i{}", bits),
+		Type::Struct(_, _) => String::from("object"),
+		Type::Tuple(_, _) => String::from("tuple"),
+		Type::UnsignedInteger(_, bits) => format!("u{}", bits),
+		Type::Void(_) => String::from("void"),
+	}
+}
+
+// splits the element type names of a tuple on the commas that are not inside of a nested tuple
+fn split_tuple_type_name(type_name: &str) -> Vec<&str> {
+	let mut element_type_names = Vec::new();
+	let mut depth = 0;
+	let mut start = 0;
+	for (i, character) in type_name.char_indices() {
+		match character {
+			'(' => depth += 1,
+			')' => depth -= 1,
+			',' if depth == 0 => {
+				element_type_names.push(type_name[start..i].trim());
+				start = i + 1;
+			},
+			_ => {},
+		}
+	}
+
+	element_type_names.push(type_name[start..].trim());
+	element_type_names
+}
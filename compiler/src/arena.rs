@@ -0,0 +1,180 @@
+use llvm_sys::core::*;
+use llvm_sys::prelude::*;
+use llvm_sys::LLVMIntPredicate;
+
+use crate::{ Block, Builder, FunctionKey, MathError, Module, Type, Value, };
+
+// an episode-scoped arena. every allocation is prefixed with a header that points to the allocation before it, so the
+// whole chain can be freed when the episode ends
+#[derive(Clone, Debug)]
+pub(crate) struct Arena {
+	pub(crate) allocate: FunctionKey,
+	pub(crate) reset: FunctionKey,
+}
+
+const HEADER_SIZE: u64 = 8;
+
+impl Module {
+	// objects created by `new` are allocated from the arena from now on, and the generated main resets the arena before
+	// every episode after the first
+	pub fn enable_arena(&mut self) {
+		if self.arena.is_some() {
+			return;
+		}
+
+		let malloc = self.create_extern_function("malloc", &vec![Type::Integer(0, 32)], Type::Void(1));
+		let free = self.create_extern_function("free", &vec![Type::Void(1)], Type::Void(0));
+
+		unsafe {
+			let pointer_type = self.to_llvm_type(Type::Void(1));
+			let head = LLVMAddGlobal(self.get_module(), pointer_type, self.string_table.to_llvm_string("arena.head"));
+			LLVMSetInitializer(head, LLVMConstNull(pointer_type));
+
+			// `arena.allocate(size)` mallocs the object together with its header, and links it into the chain
+			let allocate = self.create_function("arena.allocate", &vec![Type::Integer(0, 32)], Type::Void(1));
+			let block = self.new_block("arena.allocate", &allocate);
+
+			let size = self.function_table.get_function(&allocate).unwrap().get_argument(0);
+			let size = {
+				let builder = Builder::new();
+				builder.seek_to_end(block);
+
+				Value {
+					type_enum: Type::Integer(0, 32),
+					value: LLVMBuildAdd(
+						builder.get_builder(),
+						size.value,
+						LLVMConstInt(LLVMIntType(32), HEADER_SIZE, 0),
+						self.string_table.to_llvm_string("arenasize")
+					),
+				}
+			};
+
+			let allocation = self.add_function_call(block, &malloc, &mut [size]);
+
+			let object = {
+				let builder = Builder::new();
+				builder.seek_to_end(block);
+
+				let previous = LLVMBuildLoad2(
+					builder.get_builder(), pointer_type, head, self.string_table.to_llvm_string("arenaprevious")
+				);
+
+				LLVMBuildStore(builder.get_builder(), previous, self.get_arena_link(block, allocation.value));
+				LLVMBuildStore(builder.get_builder(), allocation.value, head);
+
+				let mut offset = [LLVMConstInt(LLVMIntType(64), HEADER_SIZE, 0)];
+				LLVMBuildGEP2(
+					builder.get_builder(),
+					LLVMIntType(8),
+					allocation.value,
+					offset.as_mut_ptr(),
+					1,
+					self.string_table.to_llvm_string("arenaobject")
+				)
+			};
+
+			self.add_return(block, Value {
+				type_enum: Type::Void(1),
+				value: object,
+			});
+
+			// `arena.reset()` walks the chain and frees every allocation in it
+			let reset = self.create_function("arena.reset", &vec![], Type::Void(0));
+			let block = self.new_block("arena.reset", &reset);
+			let condition_block = self.new_block("arena.reset.condition", &reset);
+			let body_block = self.new_block("arena.reset.body", &reset);
+			let end_block = self.new_block("arena.reset.end", &reset);
+
+			self.add_branch(block, condition_block);
+
+			let allocation = {
+				let builder = Builder::new();
+				builder.seek_to_end(condition_block);
+
+				LLVMBuildLoad2(builder.get_builder(), pointer_type, head, self.string_table.to_llvm_string("arenaallocation"))
+			};
+
+			let is_empty = {
+				let builder = Builder::new();
+				builder.seek_to_end(condition_block);
+
+				LLVMBuildICmp(
+					builder.get_builder(),
+					LLVMIntPredicate::LLVMIntEQ,
+					allocation,
+					LLVMConstNull(pointer_type),
+					self.string_table.to_llvm_string("arenaempty")
+				)
+			};
+
+			self.add_branch_if_true(
+				condition_block,
+				Value {
+					type_enum: Type::Integer(0, 1),
+					value: is_empty,
+				},
+				end_block,
+				body_block
+			).unwrap();
+
+			{
+				let builder = Builder::new();
+				builder.seek_to_end(body_block);
+
+				let previous = LLVMBuildLoad2(
+					builder.get_builder(),
+					pointer_type,
+					self.get_arena_link(body_block, allocation),
+					self.string_table.to_llvm_string("arenaprevious")
+				);
+
+				LLVMBuildStore(builder.get_builder(), previous, head);
+			}
+
+			self.add_function_call(body_block, &free, &mut [Value {
+				type_enum: Type::Void(1),
+				value: allocation,
+			}]);
+
+			self.add_branch(body_block, condition_block);
+			self.add_return_void(end_block);
+
+			self.arena = Some(Arena {
+				allocate,
+				reset,
+			});
+		}
+	}
+
+	pub fn has_arena(&self) -> bool {
+		self.arena.is_some()
+	}
+
+	// allocates `size` bytes from the arena
+	pub(crate) fn add_arena_malloc(&mut self, block: Block, size: usize) -> Result<Value, MathError> {
+		let Some(arena) = self.arena.clone() else {
+			return Err(MathError::UnsupportedOperation);
+		};
+
+		Ok(self.add_function_call(block, &arena.allocate, &mut [Value {
+			type_enum: Type::Integer(0, 32),
+			value: unsafe { LLVMConstInt(LLVMIntType(32), size as u64, 0) },
+		}]))
+	}
+
+	// the header of an allocation holds a pointer to the previous allocation
+	fn get_arena_link(&mut self, block: Block, allocation: LLVMValueRef) -> LLVMValueRef {
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			LLVMBuildBitCast(
+				builder.get_builder(),
+				allocation,
+				LLVMPointerType(self.to_llvm_type(Type::Void(1)), 0),
+				self.string_table.to_llvm_string("arenalink")
+			)
+		}
+	}
+}
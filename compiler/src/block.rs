@@ -25,6 +25,10 @@ pub enum TerminalInstruction {
 	ReturnVoid {
 		instruction: LLVMValueRef,
 	},
+	Switch {
+		default: LLVMBasicBlockRef,
+		instruction: LLVMValueRef, // cases are stored in the instruction
+	},
 	Unknown {
 		instruction: LLVMValueRef,
 	},
@@ -38,6 +42,7 @@ impl TerminalInstruction {
 			TerminalInstruction::Default { instruction, } => instruction,
 			TerminalInstruction::Return { instruction, value: _, } => instruction,
 			TerminalInstruction::ReturnVoid { instruction, } => instruction,
+			TerminalInstruction::Switch { default: _, instruction, } => instruction,
 			TerminalInstruction::None => panic!("Could not get terminal ref"),
 			TerminalInstruction::Unknown { instruction, } => instruction,
 		}
@@ -144,11 +149,14 @@ impl Module {
 			builder.seek_to_end(block);
 
 			let check_arguments = self.function_table.get_function(&function).unwrap().check_arguments;
+			let is_variadic = self.function_table.get_function(&function).unwrap().is_variadic;
 			let function_argument_types = self.function_table.get_function(&function).unwrap().argument_types.iter()
 				.map(|x| *x)
 				.collect::<Vec<Type>>();
 
-			if args.len() != function_argument_types.len() && check_arguments {
+			if (args.len() < function_argument_types.len() || (args.len() > function_argument_types.len() && !is_variadic))
+				&& check_arguments
+			{
 				panic!("Incorrect number of function arguments");
 			}
 
@@ -165,7 +173,22 @@ impl Module {
 
 			if check_arguments {
 				for (arg, arg_type) in args.iter().zip(function_argument_types.iter()) {
-					llvm_args.push(self.math_resolve_value(block, *arg, *arg_type).value);
+					llvm_args.push(self.math_resolve_value(block, *arg, *arg_type).unwrap().value);
+				}
+
+				// variadic arguments do not have a type to convert to, so they get C's default argument promotions. 32 bit
+				// floats become doubles, and booleans & integers narrower than an int become ints
+				for arg in args.iter().skip(function_argument_types.len()) {
+					let arg = self.resolve_value(block, *arg);
+					let arg = match arg.type_enum {
+						Type::Float(0, 32) => self.add_cast(block, arg, Type::Float(0, 64)).unwrap(),
+						Type::Integer(0, bits) | Type::UnsignedInteger(0, bits) if bits < 32 => {
+							self.add_cast(block, arg, Type::Integer(0, 32)).unwrap()
+						},
+						_ => arg,
+					};
+
+					llvm_args.push(arg.value);
 				}
 			} else {
 				for arg in args.iter() {
@@ -0,0 +1,97 @@
+use llvm_sys::core::*;
+
+use crate::{ Block, Builder, MathError, Module, Value };
+
+// a conditional expression, `if (condition) { a } else { b }`. each arm gets its own block, and the end block picks the
+// value of the arm that ran with a phi
+#[derive(Debug)]
+pub struct ConditionalBlock {
+	arms: Vec<(Value, Block)>, // the value of each arm, and the block the arm finished in
+	end: Block,
+	if_false: Block,
+	if_true: Block,
+}
+
+impl ConditionalBlock {
+	pub fn get_true_block(&self) -> Block {
+		self.if_true
+	}
+
+	pub fn get_false_block(&self) -> Block {
+		self.if_false
+	}
+
+	pub fn get_end(&self) -> Block {
+		self.end
+	}
+}
+
+impl Module {
+	pub fn new_conditional_block(&mut self, parent: Block, condition: Value) -> Result<ConditionalBlock, MathError> {
+		let function = self.function_table.get_function_by_ref(parent.get_parent()).unwrap();
+
+		let if_true = self.new_block("conditional_true", &function);
+		let if_false = self.new_block("conditional_false", &function);
+		let end = self.new_block("conditional_end", &function);
+
+		self.add_branch_if_true(parent, condition, if_true, if_false)?;
+
+		Ok(ConditionalBlock {
+			arms: vec![],
+			end,
+			if_false,
+			if_true,
+		})
+	}
+
+	// `block` is the block the arm finished compiling in. it is not the arm's first block if the arm created blocks of
+	// its own, i.e. a nested conditional expression
+	pub fn add_conditional_arm(&mut self, conditional: ConditionalBlock, block: Block, value: Value) -> ConditionalBlock {
+		let mut conditional = conditional;
+
+		self.add_branch(block, conditional.end);
+		conditional.arms.push((value, block));
+
+		conditional
+	}
+
+	pub fn commit_conditional_block(&mut self, conditional: ConditionalBlock) -> Result<(Value, Block), MathError> {
+		// both arms have to produce the same type for the phi
+		let mut common_type = conditional.arms[0].0.type_enum;
+		for (value, _) in conditional.arms.iter().skip(1) {
+			common_type = self.math_type_aliasing(common_type, value.type_enum)?;
+		}
+
+		// the conversions are placed in front of the branches into the end block
+		let mut incoming_values = Vec::new();
+		let mut incoming_blocks = Vec::new();
+		for (value, block) in conditional.arms.iter() {
+			incoming_values.push(self.math_resolve_value(*block, *value, common_type)?);
+			incoming_blocks.push(block.get_block());
+		}
+
+		let result_type = incoming_values[0].type_enum;
+		let mut incoming_values = incoming_values.iter().map(|value| value.value).collect::<Vec<_>>();
+
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(conditional.end);
+
+			let phi = LLVMBuildPhi(
+				builder.get_builder(),
+				self.to_llvm_type(result_type),
+				self.string_table.to_llvm_string("conditional")
+			);
+
+			LLVMAddIncoming(phi, incoming_values.as_mut_ptr(), incoming_blocks.as_mut_ptr(), incoming_values.len() as u32);
+
+			Ok((
+				Value {
+					type_enum: result_type,
+					value: phi,
+				},
+				conditional.end,
+			))
+		}
+	}
+}
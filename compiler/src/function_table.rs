@@ -2,7 +2,7 @@ use llvm_sys::core::*;
 use llvm_sys::prelude::*;
 use std::collections::HashMap;
 
-use crate::{ Block, Module, TerminalInstruction, Type, Value, };
+use crate::{ Block, MathError, Module, TerminalInstruction, Type, Value, };
 
 #[derive(Clone, Debug)]
 pub struct Function {
@@ -23,10 +23,12 @@ pub struct Function {
 	pub(crate) function: LLVMValueRef,
 	pub(crate) function_type: LLVMTypeRef,
 	pub learned_values: Vec<Value>,
+	pub learned_value_categories: Vec<usize>, // category count of each learned value, 0 if it is not a choice
 	pub name: String,
 	pub return_type: Type,
 
 	pub check_arguments: bool, // TODO remove this
+	pub is_variadic: bool, // variadic functions accept more arguments than they declare, like C's `printf`
 }
 
 impl Function {
@@ -66,8 +68,9 @@ impl Function {
 		}
 	}
 
-	pub fn add_learned_value(&mut self, value: Value) {
+	pub fn add_learned_value(&mut self, value: Value, categories: usize) {
 		self.learned_values.push(value);
+		self.learned_value_categories.push(categories);
 	}
 
 	pub fn get_argument(&self, index: usize) -> Value {
@@ -117,10 +120,12 @@ impl Module {
 			function,
 			function_type,
 			learned_values: Vec::new(),
+			learned_value_categories: Vec::new(),
 			name: self.transform_function_name(name),
 			return_type,
 
 			check_arguments: true,
+			is_variadic: false,
 		};
 
 		self.function_table.add_function(&self.transform_function_name(name), function)
@@ -128,6 +133,31 @@ impl Module {
 
 	// creates an external function and does not transform the function name
 	pub fn create_extern_function(&mut self, name: &str, arg_types: &Vec<Type>, return_type: Type) -> FunctionKey {
+		self.add_extern_function(name, arg_types, return_type, false)
+	}
+
+	// creates an external function that accepts any number of arguments after `arg_types`
+	pub fn create_variadic_extern_function(
+		&mut self, name: &str, arg_types: &Vec<Type>, return_type: Type
+	) -> FunctionKey {
+		self.add_extern_function(name, arg_types, return_type, true)
+	}
+
+	fn add_extern_function(
+		&mut self, name: &str, arg_types: &Vec<Type>, return_type: Type, is_variadic: bool
+	) -> FunctionKey {
+		// the same external function may be declared more than once, as long as the declarations agree
+		let key = FunctionKey::new(name);
+		if let Some(function) = self.function_table.get_function(&key) {
+			if function.argument_types != *arg_types || function.return_type != return_type
+				|| function.is_variadic != is_variadic
+			{
+				panic!("Extern function `{}` is already declared with a different signature", name);
+			}
+
+			return key;
+		}
+
 		let mut arguments = Vec::new();
 		for &arg_type in arg_types {
 			arguments.push(self.to_llvm_type(arg_type));
@@ -138,7 +168,7 @@ impl Module {
 		let mut argument_values = Vec::new();
 		unsafe {
 			function_type = LLVMFunctionType(
-				self.to_llvm_type(return_type), arguments.as_mut_ptr(), arguments.len() as u32, 0
+				self.to_llvm_type(return_type), arguments.as_mut_ptr(), arguments.len() as u32, is_variadic as i32
 			);
 
 			function = LLVMAddFunction(
@@ -166,14 +196,87 @@ impl Module {
 			function,
 			function_type,
 			learned_values: vec![],
+			learned_value_categories: vec![],
 			name: String::from(name),
 			return_type,
 
 			check_arguments: true,
+			is_variadic,
 		};
 
 		self.function_table.add_function(name, function)
 	}
+
+	// creates a method for a struct type. methods are regular functions with a mangled name that take the object they
+	// are called on as their first argument
+	pub fn create_method(
+		&mut self, struct_type: Type, name: &str, arg_types: &Vec<Type>, return_type: Type
+	) -> FunctionKey {
+		let Type::Struct(_, type_index) = struct_type else {
+			panic!("Methods can only be created for structs, got {:?}", struct_type);
+		};
+
+		let key = self.create_function(&self.transform_method_name(type_index, name), arg_types, return_type);
+		self.function_table.add_method(type_index, name, key.clone());
+
+		key
+	}
+
+	// looks up a function by the name it was called with. functions defined in the DSL take precedence over external
+	// functions, which keep their untransformed names
+	pub fn resolve_function(&self, name: &str) -> Result<FunctionKey, MathError> {
+		let key = FunctionKey::new(&self.transform_function_name(name));
+		if self.function_table.get_function(&key).is_some() {
+			return Ok(key);
+		}
+
+		let key = FunctionKey::new(name);
+		if self.function_table.get_function(&key).is_some() {
+			return Ok(key);
+		}
+
+		Err(MathError::UndefinedFunction(String::from(name)))
+	}
+
+	// looks up the method `name` for the type of `object`
+	pub fn get_method(&self, object: Value, name: &str) -> Result<FunctionKey, MathError> {
+		let Type::Struct(_, type_index) = object.type_enum else {
+			return Err(MathError::UnsupportedOperation);
+		};
+
+		self.function_table.get_method(type_index, name)
+			.ok_or_else(|| MathError::UndefinedFunction(self.transform_method_name(type_index, name)))
+	}
+
+	// the airt only understands numbers. objects passed to a function are flattened into their number properties, and
+	// arguments the airt cannot understand (arrays, strings) are left out
+	pub fn get_airt_inputs(&mut self, block: Block, function: &FunctionKey) -> Vec<Value> {
+		let arguments = self.function_table.get_function(function).unwrap().argument_values.clone();
+
+		let mut inputs = Vec::new();
+		for argument in arguments {
+			match argument.type_enum {
+				Type::Float(0, _) | Type::Integer(0, _) | Type::UnsignedInteger(0, _) => inputs.push(argument),
+				Type::Struct(_, type_index) => {
+					for property in self.get_number_properties(type_index) {
+						inputs.push(self.get_obj_property(block, argument, &property).unwrap());
+					}
+				},
+				_ => {},
+			}
+		}
+
+		inputs
+	}
+
+	// counts the inputs `get_airt_inputs` produces for a function
+	pub fn get_airt_input_count(&self, function: &Function) -> usize {
+		function.argument_types.iter().map(|argument_type| match argument_type {
+			Type::Float(0, _) | Type::Integer(0, _) | Type::UnsignedInteger(0, _) => 1,
+			Type::Struct(_, type_index) => self.get_number_properties(*type_index).len(),
+			_ => 0,
+		}).sum()
+	}
 }
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
@@ -193,6 +296,7 @@ impl FunctionKey {
 pub struct FunctionTable {
 	functions: HashMap<FunctionKey, Function>,
 	functions_by_ref: HashMap<LLVMValueRef, FunctionKey>,
+	methods: HashMap<(usize, String), FunctionKey>, // (struct type index, method name) -> function
 }
 
 impl FunctionTable {
@@ -207,6 +311,14 @@ impl FunctionTable {
 		return key;
 	}
 
+	pub fn add_method(&mut self, type_index: usize, name: &str, key: FunctionKey) {
+		self.methods.insert((type_index, String::from(name)), key);
+	}
+
+	pub fn get_method(&self, type_index: usize, name: &str) -> Option<FunctionKey> {
+		self.methods.get(&(type_index, String::from(name))).cloned()
+	}
+
 	pub fn get_function(&self, key: &FunctionKey) -> Option<&Function> {
 		self.functions.get(key)
 	}
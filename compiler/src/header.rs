@@ -0,0 +1,108 @@
+use llvm_sys::core::*;
+
+use crate::{ Module, Type, };
+
+impl Module {
+	// writes a C header that describes the structs, global variables, and functions of the module, so the host can read
+	// simulation state directly. should be called before `write_bitcode`, since that generates functions of its own
+	pub fn write_c_header(&self, filename: &str) {
+		let mut header = String::from("#pragma once\n\n#include <stdbool.h>\n#include <stdint.h>\n\n");
+		header.push_str("#ifdef __cplusplus\nextern \"C\" {\n#endif\n\n");
+
+		// structs refer to each other through pointers, so every struct is declared before any of them are defined
+		let struct_count = self.get_struct_count();
+		for type_index in 0..struct_count {
+			header.push_str(&format!("struct {};\n", self.lookup_struct_name(type_index)));
+		}
+
+		// tuples are stored by value, so they are defined before the structs that may contain them. LLVM returns small
+		// literal structs the same way C returns small structs
+		for type_index in 0..self.get_tuple_count() {
+			header.push_str(&format!("\nstruct tuple{} {{\n", type_index));
+			for (index, &element_type) in self.lookup_tuple_type(type_index).element_types.iter().enumerate() {
+				header.push_str(&format!("\t{};\n", self.to_c_declaration(element_type, &format!("_{}", index))));
+			}
+
+			header.push_str("};\n");
+		}
+
+		for type_index in 0..struct_count {
+			let struct_type = self.lookup_struct_type(type_index);
+
+			header.push_str(&format!("\nstruct {} {{\n", struct_type.name));
+			for property in self.get_properties(type_index) {
+				let declaration = self.to_c_declaration(struct_type.property_to_type[&property], &property);
+				header.push_str(&format!("\t{};\n", declaration));
+			}
+
+			header.push_str("};\n");
+		}
+
+		header.push('\n');
+		for (name, type_enum) in self.variable_table.get_globals() {
+			header.push_str(&format!("extern {};\n", self.to_c_declaration(type_enum, &name)));
+		}
+
+		// only functions defined in the DSL are exported. methods are left out, since their names are not C identifiers
+		let mut functions = self.function_table.iter()
+			.filter(|(_, function)| unsafe { LLVMCountBasicBlocks(function.get_function()) } > 0)
+			.filter(|(_, function)| function.name.chars().all(|character| character.is_ascii_alphanumeric() || character == '_'))
+			.map(|(_, function)| function)
+			.collect::<Vec<_>>();
+
+		functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+		header.push('\n');
+		for function in functions {
+			let mut arguments = function.argument_types.iter()
+				.map(|&argument_type| self.to_c_declaration(argument_type, ""))
+				.collect::<Vec<String>>();
+
+			if arguments.is_empty() {
+				arguments.push(String::from("void"));
+			}
+
+			header.push_str(&format!(
+				"{};\n", self.to_c_declaration(function.return_type, &format!("{}({})", function.name, arguments.join(", ")))
+			));
+		}
+
+		header.push_str("\n#ifdef __cplusplus\n}\n#endif\n");
+
+		if let Err(error) = std::fs::write(filename, header) {
+			panic!("Could not write `{}`: {}", filename, error);
+		}
+	}
+
+	// arrays are stored inline, so their size goes after the name like in C
+	fn to_c_declaration(&self, type_enum: Type, name: &str) -> String {
+		if let Type::Array(0, index) = type_enum {
+			let array_type = self.lookup_array_type(index);
+			return self.to_c_declaration(array_type.element_type, &format!("{}[{}]", name, array_type.size));
+		}
+
+		if name.is_empty() {
+			self.to_c_type(type_enum)
+		} else {
+			format!("{} {}", self.to_c_type(type_enum), name)
+		}
+	}
+
+	fn to_c_type(&self, type_enum: Type) -> String {
+		let base = match type_enum {
+			Type::Array(_, index) => self.to_c_type(self.lookup_array_type(index).element_type),
+			Type::CString(_) => String::from("char*"),
+			Type::Float(_, 32) => String::from("float"),
+			Type::Float(_, _) => String::from("double"),
+			Type::Integer(_, 1) => String::from("bool"),
+			Type::Integer(_, bits) => format!("int{}_t", bits),
+			Type::Struct(_, index) => format!("struct {}", self.lookup_struct_name(index)),
+			Type::Tuple(_, index) => format!("struct tuple{}", index),
+			Type::UnsignedInteger(_, bits) => format!("uint{}_t", bits),
+			Type::Void(_) => String::from("void"),
+		};
+
+		// a pointer to an array is a pointer to its first element in C
+		base + &"*".repeat(type_enum.get_pointer_number() as usize)
+	}
+}
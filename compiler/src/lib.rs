@@ -1,6 +1,9 @@
+pub mod arena;
 pub mod block;
 pub mod builder;
+pub mod conditional;
 pub mod function_table;
+pub mod header;
 pub mod logic;
 pub mod math;
 pub mod module;
@@ -12,6 +15,7 @@ pub mod variables;
 pub use block::Block;
 pub use block::TerminalInstruction;
 pub use builder::Builder;
+pub use conditional::ConditionalBlock;
 pub use function_table::Function;
 pub use function_table::FunctionKey;
 pub use function_table::FunctionTable;
@@ -21,6 +25,7 @@ pub use math::Value;
 pub use module::Module;
 pub(crate) use object::TypeTable;
 pub use types::Bits;
+pub use types::Conversion;
 pub use types::MathError;
 pub use types::Pointers;
 pub use types::Type;
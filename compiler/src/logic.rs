@@ -73,30 +73,20 @@ impl Module {
 	pub fn add_logic(&mut self, logic: LogicBlock, value: Value) -> Result<LogicBlock, MathError> {
 		let mut logic = logic;
 
-		let compare = if let LogicOperation::And = logic.operation {
-			self.add_equals(
-				logic.get_current_block(),
-				value,
-				self.create_immediate_integer(0)
-			)?
-		} else {
-			self.add_not_equals(
-				logic.get_current_block(),
-				value,
-				self.create_immediate_integer(0)
-			)?
-		};
-
-		// TODO have better type conversion here
 		// keep track of values so we can use the result of the logic operations in the end block
-		let value = self.math_resolve_value(logic.get_current_block(), value, Type::Integer(0, 64));
+		let value = self.add_cast(logic.get_current_block(), value, Type::Integer(0, 1))?; // tested against zero
 		logic.values.push(value);
 
 		let builder = Builder::new();
 		builder.seek_to_end(logic.get_current_block());
 
+		// `&&` stops at the first false value, `||` stops at the first true value
 		if let Some(next_block) = logic.get_next_block() {
-			self.add_branch_if_true(logic.get_current_block(), compare, logic.end, next_block);
+			if let LogicOperation::And = logic.operation {
+				self.add_branch_if_true(logic.get_current_block(), value, next_block, logic.end)?;
+			} else {
+				self.add_branch_if_true(logic.get_current_block(), value, logic.end, next_block)?;
+			}
 		} else {
 			self.add_branch(logic.get_current_block(), logic.end);
 		}
@@ -114,7 +104,7 @@ impl Module {
 
 			let phi = LLVMBuildPhi(
 				builder.get_builder(),
-				self.to_llvm_type(Type::Integer(0, 64)), // TODO comparison for floats? other bit length integers?
+				self.to_llvm_type(Type::Integer(0, 1)),
 				self.string_table.to_llvm_string("phiend")
 			);
 
@@ -132,7 +122,7 @@ impl Module {
 
 			Ok((
 				Value {
-					type_enum: Type::Integer(0, 64),
+					type_enum: Type::Integer(0, 1),
 					value: phi,
 				},
 				logic.end,
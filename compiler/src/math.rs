@@ -2,7 +2,7 @@ use llvm_sys::*;
 use llvm_sys::core::*;
 use llvm_sys::prelude::*;
 
-use crate::{ Block, Builder, MathError, Module, Type };
+use crate::{ Block, Builder, Conversion, FunctionKey, MathError, Module, Type };
 
 #[derive(Clone, Copy, Debug)]
 pub struct Value {
@@ -25,18 +25,18 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let result_type = self.math_type_aliasing(lhs.type_enum, rhs.type_enum)?;
-			let lhs = self.math_resolve_value(block, lhs, result_type);
-			let rhs = self.math_resolve_value(block, rhs, result_type);
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
 
 			let value = match result_type {
-				Type::Float(0) => LLVMBuildFAdd(
+				Type::Float(0, _) => LLVMBuildFAdd(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
 					self.string_table.to_llvm_string("addftmp")
 				),
-				Type::Integer(0, _) => LLVMBuildAdd(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildAdd(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
@@ -57,18 +57,18 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let result_type = self.math_type_aliasing(lhs.type_enum, rhs.type_enum)?;
-			let lhs = self.math_resolve_value(block, lhs, result_type);
-			let rhs = self.math_resolve_value(block, rhs, result_type);
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
 
 			let value = match result_type {
-				Type::Float(0) => LLVMBuildFSub(
+				Type::Float(0, _) => LLVMBuildFSub(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
 					self.string_table.to_llvm_string("subftmp")
 				),
-				Type::Integer(0, _) => LLVMBuildSub(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildSub(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
@@ -89,18 +89,18 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let result_type = self.math_type_aliasing(lhs.type_enum, rhs.type_enum)?;
-			let lhs = self.math_resolve_value(block, lhs, result_type);
-			let rhs = self.math_resolve_value(block, rhs, result_type);
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
 
 			let value = match result_type {
-				Type::Float(0) => LLVMBuildFMul(
+				Type::Float(0, _) => LLVMBuildFMul(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
 					self.string_table.to_llvm_string("mulftmp")
 				),
-				Type::Integer(0, _) => LLVMBuildMul(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildMul(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
@@ -121,18 +121,160 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let lhs = self.math_resolve_value(block, lhs, Type::Float(0));
-			let rhs = self.math_resolve_value(block, rhs, Type::Float(0));
+			// integers divided by integers are rounded towards zero
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
 
-			let value = LLVMBuildFDiv(
-				builder.get_builder(),
-				lhs.value,
-				rhs.value,
-				self.string_table.to_llvm_string("divtmp")
-			);
+			let value = match result_type {
+				Type::Float(0, _) => LLVMBuildFDiv(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("divtmp")
+				),
+				Type::Integer(0, _) if result_type.is_signed() => LLVMBuildSDiv(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("sdivtmp")
+				),
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildUDiv(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("udivtmp")
+				),
+				_ => return Err(MathError::UnsupportedOperation)
+			};
+
+			Ok(Value {
+				type_enum: result_type,
+				value,
+			})
+		}
+	}
+
+	// the remainder takes the sign of the left hand side, like C's `%` and `fmod`
+	pub fn add_remainder(&mut self, block: Block, lhs: Value, rhs: Value) -> Result<Value, MathError> {
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
+
+			let value = match result_type {
+				Type::Float(0, _) => LLVMBuildFRem(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("remftmp")
+				),
+				Type::Integer(0, _) if result_type.is_signed() => LLVMBuildSRem(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("sremtmp")
+				),
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildURem(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("uremtmp")
+				),
+				_ => return Err(MathError::UnsupportedOperation)
+			};
 
 			Ok(Value {
-				type_enum: Type::Float(0),
+				type_enum: result_type,
+				value,
+			})
+		}
+	}
+
+	// exponentiation goes through the `llvm.pow` intrinsic. integers are raised as doubles and converted back, so
+	// `int ** int` stays an int
+	pub fn add_power(&mut self, block: Block, lhs: Value, rhs: Value) -> Result<Value, MathError> {
+		let result_type = self.math_value_aliasing(lhs, rhs)?;
+		let float_type = match result_type {
+			Type::Float(0, bits) => Type::Float(0, bits),
+			result_type if result_type.is_integer() => Type::Float(0, 64),
+			_ => return Err(MathError::UnsupportedOperation),
+		};
+
+		let pow = self.create_extern_function(
+			&format!("llvm.pow.f{}", float_type.get_bits().unwrap()), &vec![float_type, float_type], float_type
+		);
+
+		let lhs = self.add_cast(block, lhs, float_type)?;
+		let rhs = self.add_cast(block, rhs, float_type)?;
+		let value = self.add_function_call(block, &pow, &mut [lhs, rhs]);
+
+		self.add_cast(block, value, result_type)
+	}
+
+	// shifts keep the type of the value being shifted, the shift amount is converted to it
+	pub fn add_shift_left(&mut self, block: Block, lhs: Value, rhs: Value) -> Result<Value, MathError> {
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			let lhs = self.resolve_value(block, lhs);
+			let rhs = self.resolve_value(block, rhs);
+			if !lhs.type_enum.is_integer() || !rhs.type_enum.is_integer() {
+				return Err(MathError::UnsupportedOperation)
+			}
+
+			let result_type = lhs.type_enum;
+			let rhs = self.add_cast(block, rhs, result_type)?;
+
+			Ok(Value {
+				type_enum: result_type,
+				value: LLVMBuildShl(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("shltmp")
+				),
+			})
+		}
+	}
+
+	// signed integers keep their sign when shifted right, unsigned integers are filled with zeros
+	pub fn add_shift_right(&mut self, block: Block, lhs: Value, rhs: Value) -> Result<Value, MathError> {
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			let lhs = self.resolve_value(block, lhs);
+			let rhs = self.resolve_value(block, rhs);
+			if !lhs.type_enum.is_integer() || !rhs.type_enum.is_integer() {
+				return Err(MathError::UnsupportedOperation)
+			}
+
+			let result_type = lhs.type_enum;
+			let rhs = self.add_cast(block, rhs, result_type)?;
+
+			let value = if result_type.is_signed() {
+				LLVMBuildAShr(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("ashrtmp")
+				)
+			} else {
+				LLVMBuildLShr(
+					builder.get_builder(),
+					lhs.value,
+					rhs.value,
+					self.string_table.to_llvm_string("lshrtmp")
+				)
+			};
+
+			Ok(Value {
+				type_enum: result_type,
 				value,
 			})
 		}
@@ -150,15 +292,18 @@ impl Module {
 		}
 	}
 
-	fn get_integer_compare_enum(&self, operation: CompareOperation) -> LLVMIntPredicate {
-		// TODO unsigned int problems
-		match operation {
-			CompareOperation::Equals => LLVMIntPredicate::LLVMIntEQ,
-			CompareOperation::GreaterThan => LLVMIntPredicate::LLVMIntUGT,
-			CompareOperation::GreaterThanEqualTo => LLVMIntPredicate::LLVMIntUGE,
-			CompareOperation::LessThan => LLVMIntPredicate::LLVMIntULT,
-			CompareOperation::LessThanEqualTo => LLVMIntPredicate::LLVMIntULE,
-			CompareOperation::NotEquals => LLVMIntPredicate::LLVMIntNE,
+	fn get_integer_compare_enum(&self, operation: CompareOperation, is_signed: bool) -> LLVMIntPredicate {
+		match (operation, is_signed) {
+			(CompareOperation::Equals, _) => LLVMIntPredicate::LLVMIntEQ,
+			(CompareOperation::GreaterThan, false) => LLVMIntPredicate::LLVMIntUGT,
+			(CompareOperation::GreaterThan, true) => LLVMIntPredicate::LLVMIntSGT,
+			(CompareOperation::GreaterThanEqualTo, false) => LLVMIntPredicate::LLVMIntUGE,
+			(CompareOperation::GreaterThanEqualTo, true) => LLVMIntPredicate::LLVMIntSGE,
+			(CompareOperation::LessThan, false) => LLVMIntPredicate::LLVMIntULT,
+			(CompareOperation::LessThan, true) => LLVMIntPredicate::LLVMIntSLT,
+			(CompareOperation::LessThanEqualTo, false) => LLVMIntPredicate::LLVMIntULE,
+			(CompareOperation::LessThanEqualTo, true) => LLVMIntPredicate::LLVMIntSLE,
+			(CompareOperation::NotEquals, _) => LLVMIntPredicate::LLVMIntNE,
 		}
 	}
 
@@ -169,23 +314,27 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let common_type = self.math_type_aliasing(lhs.type_enum, rhs.type_enum)?;
-			let lhs = self.math_resolve_value(block, lhs, common_type);
-			let rhs = self.math_resolve_value(block, rhs, common_type);
+			let common_type = self.math_value_aliasing(lhs, rhs)?;
+			let lhs = self.math_resolve_value(block, lhs, common_type)?;
+			let rhs = self.math_resolve_value(block, rhs, common_type)?;
+
+			if let Type::CString(_) = common_type {
+				return self.add_string_compare(block, lhs, rhs, operation);
+			}
 
 			let value = Value {
 				type_enum: Type::Integer(0, 1),
 				value: match common_type {
-					Type::Float(_) => LLVMBuildFCmp(
+					Type::Float(_, _) => LLVMBuildFCmp(
 						builder.get_builder(),
 						self.get_float_compare_enum(operation),
 						lhs.value,
 						rhs.value,
 						self.string_table.to_llvm_string("fcmp")
 					),
-					Type::Integer(_, 64) => LLVMBuildICmp(
+					Type::Integer(_, _) | Type::UnsignedInteger(_, _) => LLVMBuildICmp(
 						builder.get_builder(),
-						self.get_integer_compare_enum(operation),
+						self.get_integer_compare_enum(operation, common_type.is_signed()),
 						lhs.value,
 						rhs.value,
 						self.string_table.to_llvm_string("icmp")
@@ -198,6 +347,34 @@ impl Module {
 		}
 	}
 
+	// strings are compared by content through `strcmp`, which needs to be declared by the code generator
+	fn add_string_compare(
+		&mut self, block: Block, lhs: Value, rhs: Value, operation: CompareOperation
+	) -> Result<Value, MathError> {
+		match operation {
+			CompareOperation::Equals | CompareOperation::NotEquals => {},
+			_ => return Err(MathError::UnsupportedOperation),
+		}
+
+		let result = self.add_function_call(block, &FunctionKey::new("strcmp"), &mut [lhs, rhs]);
+
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			Ok(Value {
+				type_enum: Type::Integer(0, 1),
+				value: LLVMBuildICmp(
+					builder.get_builder(),
+					self.get_integer_compare_enum(operation, true),
+					result.value,
+					LLVMConstInt(self.to_llvm_type(result.type_enum), 0, 0),
+					self.string_table.to_llvm_string("scmp")
+				),
+			})
+		}
+	}
+
 	pub fn add_less_than(&mut self, block: Block, lhs: Value, rhs: Value) -> Result<Value, MathError> {
 		self.add_compare(block, lhs, rhs, CompareOperation::LessThan)
 	}
@@ -227,17 +404,17 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let result_type = self.math_type_aliasing(lhs.type_enum, rhs.type_enum)?;
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
 
-			if result_type.zero_pointer_number().zero_bits() != Type::Integer(0, 0) {
+			if !result_type.is_integer() {
 				return Err(MathError::UnsupportedOperation)
 			}
 
-			let lhs = self.math_resolve_value(block, lhs, result_type);
-			let rhs = self.math_resolve_value(block, rhs, result_type);
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
 
 			let value = match result_type {
-				Type::Integer(0, _) => LLVMBuildAnd(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildAnd(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
@@ -258,17 +435,17 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let result_type = self.math_type_aliasing(lhs.type_enum, rhs.type_enum)?;
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
 
-			if result_type.zero_pointer_number().zero_bits() != Type::Integer(0, 0) {
+			if !result_type.is_integer() {
 				return Err(MathError::UnsupportedOperation)
 			}
 
-			let lhs = self.math_resolve_value(block, lhs, result_type);
-			let rhs = self.math_resolve_value(block, rhs, result_type);
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
 
 			let value = match result_type {
-				Type::Integer(0, _) => LLVMBuildOr(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildOr(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
@@ -289,17 +466,17 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let result_type = self.math_type_aliasing(lhs.type_enum, rhs.type_enum)?;
+			let result_type = self.math_value_aliasing(lhs, rhs)?;
 
-			if result_type.zero_pointer_number().zero_bits() != Type::Integer(0, 0) {
+			if !result_type.is_integer() {
 				return Err(MathError::UnsupportedOperation)
 			}
 
-			let lhs = self.math_resolve_value(block, lhs, result_type);
-			let rhs = self.math_resolve_value(block, rhs, result_type);
+			let lhs = self.math_resolve_value(block, lhs, result_type)?;
+			let rhs = self.math_resolve_value(block, rhs, result_type)?;
 
 			let value = match result_type {
-				Type::Integer(0, _) => LLVMBuildXor(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildXor(
 					builder.get_builder(),
 					lhs.value,
 					rhs.value,
@@ -322,16 +499,16 @@ impl Module {
 
 			let result_type = value.type_enum.zero_pointer_number();
 
-			if result_type.zero_pointer_number().zero_bits() != Type::Integer(0, 0) {
+			if !result_type.is_integer() {
 				return Err(MathError::UnsupportedOperation)
 			}
 
-			let value = self.math_resolve_value(block, value, result_type);
+			let value = self.math_resolve_value(block, value, result_type)?;
 
 			let value = match result_type {
-				Type::Integer(0, bits) => LLVMBuildXor(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildXor(
 					builder.get_builder(),
-					LLVMConstInt(self.to_llvm_type(Type::Integer(0, bits)), 0xFFFF_FFFF_FFFF_FFFF, 0), // input tied high
+					LLVMConstAllOnes(self.to_llvm_type(result_type)), // input tied high
 					value.value,
 					self.string_table.to_llvm_string("xortmp")
 				),
@@ -347,9 +524,20 @@ impl Module {
 
 	pub fn add_logical_not(&mut self, block: Block, value: Value) -> Result<Value, MathError> {
 		match value.type_enum {
-			Type::Float(_) =>
+			Type::Integer(_, 1) => unsafe {
+				let builder = Builder::new();
+				builder.seek_to_end(block);
+
+				let value = self.resolve_value(block, value);
+
+				Ok(Value {
+					type_enum: Type::Integer(0, 1),
+					value: LLVMBuildNot(builder.get_builder(), value.value, self.string_table.to_llvm_string("nottmp")),
+				})
+			},
+			Type::Float(_, _) =>
 				self.add_compare(block, value, self.create_immediate_float(0.0), CompareOperation::Equals),
-			Type::Integer(_, _) =>
+			Type::Integer(_, _) | Type::UnsignedInteger(_, _) =>
 				self.add_compare(block, value, self.create_immediate_integer(0), CompareOperation::Equals),
 			_ => Err(MathError::UnsupportedOperation)
 		}
@@ -361,18 +549,18 @@ impl Module {
 			builder.seek_to_end(block);
 
 			let result_type = value.type_enum.zero_pointer_number();
-			let value = self.math_resolve_value(block, value, result_type);
+			let value = self.math_resolve_value(block, value, result_type)?;
 
 			let value = match result_type {
-				Type::Float(0) => LLVMBuildFSub(
+				Type::Float(0, _) => LLVMBuildFSub(
 					builder.get_builder(),
-					LLVMConstReal(self.to_llvm_type(Type::Float(0)), 0.0),
+					LLVMConstReal(self.to_llvm_type(result_type), 0.0),
 					value.value,
 					self.string_table.to_llvm_string("subftmp")
 				),
-				Type::Integer(0, bits) => LLVMBuildSub(
+				Type::Integer(0, _) | Type::UnsignedInteger(0, _) => LLVMBuildSub(
 					builder.get_builder(),
-					LLVMConstInt(self.to_llvm_type(Type::Integer(0, bits)), 0, 0),
+					LLVMConstInt(self.to_llvm_type(result_type), 0, 0),
 					value.value,
 					self.string_table.to_llvm_string("subtmp")
 				),
@@ -386,31 +574,117 @@ impl Module {
 		}
 	}
 
+	// picks between two values without branching. both values are converted to the type they have in common
+	pub fn add_select(
+		&mut self, block: Block, condition: Value, if_true: Value, if_false: Value
+	) -> Result<Value, MathError> {
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			let common_type = self.math_value_aliasing(if_true, if_false)?;
+			let condition = self.add_cast(block, condition, Type::Integer(0, 1))?; // conditions are tested against zero
+			let if_true = self.math_resolve_value(block, if_true, common_type)?;
+			let if_false = self.math_resolve_value(block, if_false, common_type)?;
+
+			Ok(Value {
+				type_enum: common_type,
+				value: LLVMBuildSelect(
+					builder.get_builder(),
+					condition.value,
+					if_true.value,
+					if_false.value,
+					self.string_table.to_llvm_string("select")
+				),
+			})
+		}
+	}
+
+	// a constant takes the type of the value it is combined with if it fits into it, so `x / 2` stays unsigned for an
+	// unsigned `x` and `x * 2.0` stays a float32 for a float32 `x`. everything else is aliased by type
+	pub fn math_value_aliasing(&self, lhs: Value, rhs: Value) -> Result<Type, MathError> {
+		let is_constant = |value: Value| unsafe {
+			value.type_enum.get_pointer_number() == 0 && LLVMIsConstant(value.value) != 0
+		};
+
+		let takes_type = |constant: Value, other: Value| {
+			let other_type = other.type_enum.zero_pointer_number();
+			let is_same_kind = match (constant.type_enum, other_type) {
+				(Type::Float(_, _), Type::Float(_, _)) => true,
+				(type1, type2) => type1.is_integer() && type2.is_integer()
+					&& type1.get_bits() != Some(1) && type2.get_bits() != Some(1),
+			};
+
+			is_same_kind && self.convert_constant(constant, other_type).is_ok()
+		};
+
+		match (is_constant(lhs), is_constant(rhs)) {
+			(true, false) if takes_type(lhs, rhs) => Ok(rhs.type_enum.zero_pointer_number()),
+			(false, true) if takes_type(rhs, lhs) => Ok(lhs.type_enum.zero_pointer_number()),
+			_ => self.math_type_aliasing(lhs.type_enum, rhs.type_enum),
+		}
+	}
+
 	pub fn math_type_aliasing(&self, type1: Type, type2: Type) -> Result<Type, MathError> {
-		if let Type::Integer(_, bits1) = type1 {
-			if let Type::Integer(_, bits2) = type2 {
-				return Ok(Type::Integer(0, std::cmp::max(bits1, bits2)));
-			}
-		} else if let Type::Integer(_, bits2) = type2 {
-			if let Type::Integer(_, bits1) = type1 {
+		// integer math is only unsigned if both sides are unsigned
+		match (type1, type2) {
+			(Type::UnsignedInteger(_, bits1), Type::UnsignedInteger(_, bits2)) => {
+				return Ok(Type::UnsignedInteger(0, std::cmp::max(bits1, bits2)));
+			},
+			(Type::Integer(_, bits1), Type::Integer(_, bits2)) => {
 				return Ok(Type::Integer(0, std::cmp::max(bits1, bits2)));
-			}
+			},
+			// mixed signedness promotes to a signed type that holds every value of both sides. there is no signed type
+			// wider than a u64, so those need an explicit `as`
+			(Type::Integer(_, signed_bits), Type::UnsignedInteger(_, unsigned_bits))
+				| (Type::UnsignedInteger(_, unsigned_bits), Type::Integer(_, signed_bits)) => {
+				if signed_bits == 1 { // booleans do not have a sign
+					return Ok(Type::UnsignedInteger(0, unsigned_bits));
+				} else if unsigned_bits >= 64 {
+					return Err(MathError::IncompatibleTypes(type1, type2));
+				} else {
+					return Ok(Type::Integer(0, std::cmp::max(signed_bits, unsigned_bits * 2)));
+				}
+			},
+			_ => {},
 		}
 
 		if type1.zero_pointer_number() == type2.zero_pointer_number() {
 			Ok(type1.zero_pointer_number())
-		} else if type1.zero_pointer_number() == Type::Float(0) || type2.zero_pointer_number() == Type::Float(0) {
-			Ok(Type::Float(0))
+		} else if let (Type::CString(_), _) | (_, Type::CString(_)) = (type1, type2) { // strings do not mix with numbers
+			Err(MathError::IncompatibleTypes(type1, type2))
+		} else if let (Type::Float(_, bits1), Type::Float(_, bits2)) = (type1, type2) {
+			Ok(Type::Float(0, std::cmp::max(bits1, bits2)))
+		} else if let (Type::Float(_, bits), _) | (_, Type::Float(_, bits)) = (type1, type2) { // integers become floats
+			Ok(Type::Float(0, bits))
 		} else {
 			Err(MathError::IncompatibleTypes(type1, type2))
 		}
 	}
 
-	pub fn create_immediate_integer(&self, number: u64) -> Value {
+	pub fn create_immediate_integer(&self, number: i64) -> Value {
 		unsafe {
 			Value {
 				type_enum: Type::Integer(0, 64),
-				value: LLVMConstInt(self.to_llvm_type(Type::Integer(0, 64)), number, 0),
+				value: LLVMConstInt(self.to_llvm_type(Type::Integer(0, 64)), number as u64, 1),
+			}
+		}
+	}
+
+	pub fn create_immediate_boolean(&self, value: bool) -> Value {
+		unsafe {
+			Value {
+				type_enum: Type::Integer(0, 1),
+				value: LLVMConstInt(self.to_llvm_type(Type::Integer(0, 1)), value as u64, 0),
+			}
+		}
+	}
+
+	pub fn create_immediate_unsigned_integer(&self, number: u64) -> Value {
+		unsafe {
+			Value {
+				type_enum: Type::UnsignedInteger(0, 64),
+				value: LLVMConstInt(self.to_llvm_type(Type::UnsignedInteger(0, 64)), number, 0),
 			}
 		}
 	}
@@ -438,8 +712,17 @@ impl Module {
 	pub fn create_immediate_float(&self, number: f64) -> Value {
 		unsafe {
 			Value {
-				type_enum: Type::Float(0),
-				value: LLVMConstReal(self.to_llvm_type(Type::Float(0)), number),
+				type_enum: Type::Float(0, 64),
+				value: LLVMConstReal(self.to_llvm_type(Type::Float(0, 64)), number),
+			}
+		}
+	}
+
+	pub fn create_immediate_float32(&self, number: f32) -> Value {
+		unsafe {
+			Value {
+				type_enum: Type::Float(0, 32),
+				value: LLVMConstReal(self.to_llvm_type(Type::Float(0, 32)), number as f64),
 			}
 		}
 	}
@@ -451,14 +734,14 @@ impl Module {
 
 			let global = LLVMAddGlobal(
 				self.get_module(),
-				self.to_llvm_type(Type::Float(0)),
+				self.to_llvm_type(Type::Float(0, 64)),
 				self.string_table.to_llvm_string(&format!("cf{}", number as u64))
 			);
 
-			LLVMSetInitializer(global, LLVMConstReal(self.to_llvm_type(Type::Float(0)), number));
+			LLVMSetInitializer(global, LLVMConstReal(self.to_llvm_type(Type::Float(0, 64)), number));
 
 			Value {
-				type_enum: Type::Float(1),
+				type_enum: Type::Float(1, 64),
 				value: global,
 			}
 		}
@@ -466,18 +749,26 @@ impl Module {
 
 	pub fn upgrade_type(&mut self, type_enum: Type) -> Type {
 		match type_enum {
-			Type::Float(pointer_number) => Type::Float(pointer_number + 1),
+			Type::Array(pointer_number, index) => Type::Array(pointer_number + 1, index),
+			Type::CString(pointer_number) => Type::CString(pointer_number + 1),
+			Type::Float(pointer_number, bits) => Type::Float(pointer_number + 1, bits),
 			Type::Integer(pointer_number, bits) => Type::Integer(pointer_number + 1, bits),
 			Type::Struct(pointer_number, index) => Type::Struct(pointer_number + 1, index),
+			Type::Tuple(pointer_number, index) => Type::Tuple(pointer_number + 1, index),
+			Type::UnsignedInteger(pointer_number, bits) => Type::UnsignedInteger(pointer_number + 1, bits),
 			_ => todo!(),
 		}
 	}
 
 	pub fn downgrade_type(&mut self, type_enum: Type) -> Type {
 		match type_enum {
-			Type::Float(pointer_number) => Type::Float(pointer_number - 1),
+			Type::Array(pointer_number, index) => Type::Array(pointer_number - 1, index),
+			Type::CString(pointer_number) => Type::CString(pointer_number - 1),
+			Type::Float(pointer_number, bits) => Type::Float(pointer_number - 1, bits),
 			Type::Integer(pointer_number, bits) => Type::Integer(pointer_number - 1, bits),
 			Type::Struct(pointer_number, index) => Type::Struct(pointer_number - 1, index),
+			Type::Tuple(pointer_number, index) => Type::Tuple(pointer_number - 1, index),
+			Type::UnsignedInteger(pointer_number, bits) => Type::UnsignedInteger(pointer_number - 1, bits),
 			_ => todo!(),
 		}
 	}
@@ -489,128 +780,207 @@ impl Module {
 			builder.seek_to_end(block);
 
 			match value.type_enum { // TODO modularize
-				Type::Float(pointer_number) => {
+				Type::CString(pointer_number) => {
 					if pointer_number == 0 {
 						value
 					} else {
 						Value {
-							type_enum: Type::Float(0),
+							type_enum: Type::CString(0),
 							value: LLVMBuildLoad2(
 								builder.get_builder(),
-								self.to_llvm_type(Type::Float(0)),
+								self.to_llvm_type(Type::CString(0)),
+								value.value,
+								self.string_table.to_llvm_string("sdereference")
+							),
+						}
+					}
+				},
+				Type::Float(pointer_number, _) => {
+					if pointer_number == 0 {
+						value
+					} else {
+						Value {
+							type_enum: value.type_enum.zero_pointer_number(),
+							value: LLVMBuildLoad2(
+								builder.get_builder(),
+								self.to_llvm_type(value.type_enum.zero_pointer_number()),
 								value.value,
 								self.string_table.to_llvm_string("fdereference")
 							),
 						}
 					}
 				},
-				Type::Integer(pointer_number, bits) => {
+				Type::Integer(pointer_number, _) | Type::UnsignedInteger(pointer_number, _) => {
 					if pointer_number == 0 {
 						value
 					} else {
 						Value {
-							type_enum: Type::Integer(0, bits),
+							type_enum: value.type_enum.zero_pointer_number(),
 							value: LLVMBuildLoad2(
 								builder.get_builder(),
-								self.to_llvm_type(Type::Integer(0, bits)),
+								self.to_llvm_type(value.type_enum.zero_pointer_number()),
 								value.value,
 								self.string_table.to_llvm_string("idereference")
 							),
 						}
 					}
 				},
+				Type::Struct(pointer_number, index) => { // objects are always handled through one pointer
+					if pointer_number <= 1 {
+						value
+					} else {
+						Value {
+							type_enum: Type::Struct(pointer_number - 1, index),
+							value: LLVMBuildLoad2(
+								builder.get_builder(),
+								self.to_llvm_type(Type::Struct(pointer_number - 1, index)),
+								value.value,
+								self.string_table.to_llvm_string("odereference")
+							),
+						}
+					}
+				},
+				Type::Tuple(pointer_number, _) => { // tuples are passed around by value
+					if pointer_number == 0 {
+						value
+					} else {
+						Value {
+							type_enum: value.type_enum.zero_pointer_number(),
+							value: LLVMBuildLoad2(
+								builder.get_builder(),
+								self.to_llvm_type(value.type_enum.zero_pointer_number()),
+								value.value,
+								self.string_table.to_llvm_string("tdereference")
+							),
+						}
+					}
+				},
 				_ => value,
 			}
 		}
 	}
 
-	pub fn math_resolve_value(&mut self, block: Block, value: Value, result_type: Type) -> Value {
+	pub fn math_resolve_value(&mut self, block: Block, value: Value, result_type: Type) -> Result<Value, MathError> {
 		let resolved = self.resolve_value(block, value);
 		self.convert_to_type(block, resolved, result_type)
 	}
 
-	pub fn convert_to_type(&mut self, block: Block, value: Value, result_type: Type) -> Value {
+	// implicit conversions warn when they lose precision, use `add_cast` for conversions that were asked for. constants
+	// that fit into the new type exactly do not lose anything
+	pub fn convert_to_type(&mut self, block: Block, value: Value, result_type: Type) -> Result<Value, MathError> {
+		// tuples of different types are converted element by element
+		if let (Type::Tuple(0, index1), Type::Tuple(0, index2)) = (value.type_enum, result_type) {
+			if index1 != index2 {
+				return self.convert_tuple_to_type(block, value, result_type);
+			}
+		}
+
+		let conversion = value.type_enum.get_conversion(&result_type)?;
+
+		if conversion.loses_precision() && self.convert_constant(value, result_type).is_err() {
+			let location = self.source_location.as_ref()
+				.map(|location| format!("{}: ", location))
+				.unwrap_or_default();
+
+			eprintln!(
+				"warning: {}implicit conversion from {:?} to {:?} loses precision, use `as` to silence this warning",
+				location,
+				value.type_enum,
+				result_type
+			);
+		}
+
+		Ok(self.build_conversion(block, value, result_type, conversion))
+	}
+
+	// explicit conversions, i.e. `value as int`
+	pub fn add_cast(&mut self, block: Block, value: Value, result_type: Type) -> Result<Value, MathError> {
+		let value = self.resolve_value(block, value);
+		let conversion = value.type_enum.get_conversion(&result_type)?;
+		Ok(self.build_conversion(block, value, result_type, conversion))
+	}
+
+	fn build_conversion(&mut self, block: Block, value: Value, result_type: Type, conversion: Conversion) -> Value {
+		if conversion == Conversion::Identity {
+			return value;
+		}
+
 		unsafe {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			if value.type_enum == result_type {
-				return value;
-			}
+			let value = match conversion {
+				Conversion::Decay => value.value, // arrays decay into void pointers
+				Conversion::Extend => LLVMBuildIntCast2(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					value.type_enum.is_signed() as i32,
+					self.string_table.to_llvm_string("iupgrade")
+				),
+				Conversion::FloatExtend => LLVMBuildFPExt(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					self.string_table.to_llvm_string("fupgrade")
+				),
+				Conversion::FloatTruncate => LLVMBuildFPTrunc(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					self.string_table.to_llvm_string("ftrunc")
+				),
+				Conversion::FloatToBoolean => LLVMBuildFCmp(
+					builder.get_builder(),
+					LLVMRealPredicate::LLVMRealUNE,
+					value.value,
+					LLVMConstReal(self.to_llvm_type(value.type_enum), 0.0),
+					self.string_table.to_llvm_string("bcast")
+				),
+				Conversion::FloatToInteger if result_type.is_signed() => LLVMBuildFPToSI(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					self.string_table.to_llvm_string("icast")
+				),
+				Conversion::FloatToInteger => LLVMBuildFPToUI(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					self.string_table.to_llvm_string("icast")
+				),
+				Conversion::Identity => unreachable!(),
+				Conversion::IntegerToBoolean => LLVMBuildICmp(
+					builder.get_builder(),
+					LLVMIntPredicate::LLVMIntNE,
+					value.value,
+					LLVMConstInt(self.to_llvm_type(value.type_enum), 0, 0),
+					self.string_table.to_llvm_string("bcast")
+				),
+				Conversion::IntegerToFloat if value.type_enum.is_signed() => LLVMBuildSIToFP(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					self.string_table.to_llvm_string("fcast")
+				),
+				Conversion::IntegerToFloat => LLVMBuildUIToFP(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					self.string_table.to_llvm_string("fcast")
+				),
+				Conversion::Reinterpret => value.value, // signedness only exists in the type system
+				Conversion::Truncate => LLVMBuildTrunc(
+					builder.get_builder(),
+					value.value,
+					self.to_llvm_type(result_type),
+					self.string_table.to_llvm_string("itrunc")
+				),
+			};
 
-			match value.type_enum {
-				Type::Float(pointer_number) => match result_type {
-					Type::Integer(_, bits) => Value {
-						type_enum: Type::Integer(pointer_number, bits),
-						value: LLVMBuildFPToUI(
-							builder.get_builder(),
-							value.value,
-							self.to_llvm_type(Type::Integer(0, bits)),
-							self.string_table.to_llvm_string("icast")
-						),
-					},
-					Type::Float(_) => value,
-					_ => todo!("{:?} {:?}", value.type_enum, result_type),
-				},
-				Type::FloatArray(_) => {
-					match result_type {
-						Type::Float(pointer_number) => {
-							if pointer_number == 1 {
-								Value {
-									type_enum: Type::Float(1),
-									value: LLVMBuildAddrSpaceCast(
-										builder.get_builder(),
-										value.value,
-										self.to_llvm_type(Type::Float(1)),
-										self.string_table.to_llvm_string("addrspacecast"),
-									),
-								}
-							} else {
-								todo!();
-							}
-						},
-						_ => todo!("{:?} {:?}", value.type_enum, result_type),
-					}
-				},
-				Type::Integer(pointer_number, bits1) => match result_type {
-					Type::Integer(_, bits2) => {
-						if bits1 == bits2 {
-							value
-						} else if bits1 > bits2 { // no bit truncating yet
-							Value {
-								type_enum: Type::Integer(pointer_number, bits2),
-								value: LLVMBuildTrunc(
-									builder.get_builder(),
-									value.value,
-									self.to_llvm_type(Type::Integer(pointer_number, bits2)),
-									self.string_table.to_llvm_string("itrunc")
-								),
-							}
-						} else {
-							Value {
-								type_enum: Type::Integer(pointer_number, bits2),
-								value: LLVMBuildIntCast2(
-									builder.get_builder(),
-									value.value,
-									self.to_llvm_type(Type::Integer(pointer_number, bits2)), // upgrade bits, retain value pointer number
-									0,
-									self.string_table.to_llvm_string("iupgrade")
-								),
-							}
-						}
-					},
-					Type::Float(_) =>	Value {
-						type_enum: Type::Float(pointer_number),
-						value: LLVMBuildUIToFP(
-							builder.get_builder(),
-							value.value,
-							self.to_llvm_type(Type::Float(0)),
-							self.string_table.to_llvm_string("fcast")
-						),
-					},
-					_ => todo!("{:?} {:?}", value.type_enum, result_type),
-				}
-				_ => todo!("{:?} {:?}", value.type_enum, result_type),
+			Value {
+				type_enum: result_type,
+				value,
 			}
 		}
 	}
@@ -3,15 +3,19 @@ use llvm_sys::bit_writer::*;
 use llvm_sys::core::*;
 use llvm_sys::prelude::*;
 
+use crate::arena::Arena;
 use crate::object::TypeTable;
-use crate::{ Block, Builder, Function, FunctionKey, FunctionTable, TerminalInstruction, Type, Value, VariableTable, };
+use crate::{ Block, Builder, Function, FunctionKey, FunctionTable, MathError, TerminalInstruction, Type, Value, VariableTable, };
 use crate::strings::StringTable;
 
 #[derive(Debug)]
 pub struct Module {
+	pub(crate) arena: Option<Arena>, // objects are allocated from an episode-scoped arena instead of the heap if set
 	context: LLVMContextRef,
 	pub function_table: FunctionTable,
+	pub(crate) global_constructor: Option<(FunctionKey, Block)>, // initializes global variables before `_main` runs
 	module: LLVMModuleRef,
+	pub(crate) source_location: Option<String>, // where the code that is being compiled comes from, used in warnings
 	pub string_table: StringTable, // keep the strings alive for as long as we are using LLVM resources
 	pub type_table: TypeTable,
 	pub variable_table: VariableTable,
@@ -29,33 +33,62 @@ impl Module {
 		}
 
 		Module {
+			arena: None,
 			context,
 			function_table: FunctionTable::default(),
+			global_constructor: None,
 			module,
+			source_location: None,
 			string_table,
 			type_table: TypeTable::default(),
 			variable_table: VariableTable::default(),
 		}
 	}
 
+	// `file:line:column` of the code that is being compiled
+	pub fn set_source_location(&mut self, location: String) {
+		self.source_location = Some(location);
+	}
+
 	pub fn to_llvm_type(&self, type_enum: Type) -> LLVMTypeRef {
 		unsafe {
 			match type_enum {
+				Type::Array(0, index) => {
+					self.lookup_array_type(index).type_ref
+				},
+				Type::Array(1, index) => {
+					LLVMPointerType(self.lookup_array_type(index).type_ref, 0)
+				},
 				Type::CString(0) => {
 					let i8_type = LLVMIntType(8);
 					LLVMPointerType(i8_type, 0)
 				},
-				Type::Float(0) => LLVMDoubleType(),
-				Type::Float(1) => LLVMPointerType(LLVMDoubleType(), 0),
-				Type::FloatArray(size) => LLVMArrayType(LLVMDoubleType(), size as u32),
+				Type::CString(1) => {
+					let i8_type = LLVMIntType(8);
+					LLVMPointerType(LLVMPointerType(i8_type, 0), 0)
+				},
+				Type::Float(0, 32) => LLVMFloatType(),
+				Type::Float(0, 64) => LLVMDoubleType(),
+				Type::Float(1, bits) => LLVMPointerType(self.to_llvm_type(Type::Float(0, bits)), 0),
 				Type::Integer(0, bits) => LLVMIntType(bits),
 				Type::Integer(1, bits) => LLVMPointerType(LLVMIntType(bits), 0),
+				Type::UnsignedInteger(0, bits) => LLVMIntType(bits),
+				Type::UnsignedInteger(1, bits) => LLVMPointerType(LLVMIntType(bits), 0),
 				Type::Struct(0, index) => {
 					self.lookup_struct_type(index).type_ref
 				},
 				Type::Struct(1, index) => {
 					LLVMPointerType(self.lookup_struct_type(index).type_ref, 0)
 				},
+				Type::Struct(2, index) => {
+					LLVMPointerType(LLVMPointerType(self.lookup_struct_type(index).type_ref, 0), 0)
+				},
+				Type::Tuple(0, index) => {
+					self.lookup_tuple_type(index).type_ref
+				},
+				Type::Tuple(1, index) => {
+					LLVMPointerType(self.lookup_tuple_type(index).type_ref, 0)
+				},
 				Type::Void(0) => LLVMVoidType(),
 				Type::Void(1) => LLVMPointerTypeInContext(self.get_context(), 0),
 				_ => todo!("{:?}", type_enum),
@@ -67,7 +100,7 @@ impl Module {
 		// add airt function references
 		let airt_register_function = self.create_extern_function(
 			"airt_register_function",
-			&vec![Type::CString(0), Type::Integer(0, 64), Type::Integer(0, 64)],
+			&vec![Type::CString(0), Type::Integer(0, 64), Type::Integer(0, 64), Type::Void(1)],
 			Type::Void(0)
 		);
 
@@ -78,7 +111,7 @@ impl Module {
 			unsafe {
 				let mut arguments = vec![
 					LLVMPointerType(LLVMFunctionType(self.to_llvm_type(Type::Integer(0, 64)), [].as_mut_ptr(), 0 as u32, 0), 0),
-					LLVMPointerType(LLVMFunctionType(self.to_llvm_type(Type::Float(0)), [].as_mut_ptr(), 0 as u32, 0), 0),
+					LLVMPointerType(LLVMFunctionType(self.to_llvm_type(Type::Float(0, 64)), [].as_mut_ptr(), 0 as u32, 0), 0),
 				];
 
 				function_type = LLVMFunctionType(
@@ -100,10 +133,12 @@ impl Module {
 				function,
 				function_type,
 				learned_values: vec![],
+				learned_value_categories: vec![],
 				name: String::from("airt_init"),
 				return_type: Type::Void(0),
 
 				check_arguments: false,
+				is_variadic: false,
 			};
 
 			self.function_table.add_function("airt_init", function)
@@ -115,8 +150,24 @@ impl Module {
 		let main_function = self.create_extern_function("main", &vec![], Type::Integer(0, 64));
 		let main_block = self.new_block("main", &main_function);
 
-		// add call to `airt_init`
-		let reset_function = self.function_table.get_function(&FunctionKey::new("_main")).unwrap().function;
+		// add call to `airt_init`. every episode initializes the global variables again before `_main` runs. with an
+		// arena, episodes after the first also free the objects of the last episode
+		let episode_function = self.create_function("episode.reset", &vec![], Type::Integer(0, 64));
+		let block = self.new_block("episode.reset", &episode_function);
+
+		if let Some(arena) = self.arena.clone() {
+			self.add_function_call(block, &arena.reset, &mut []);
+		}
+
+		if let Some((global_constructor, _)) = self.global_constructor.clone() {
+			self.add_function_call(block, &global_constructor, &mut []);
+		}
+
+		let value = self.add_function_call(block, &FunctionKey::new("_main"), &mut []);
+		self.add_return(block, value);
+
+		let reset_function = self.function_table.get_function(&episode_function).unwrap().function;
+
 		let tick_function = self.function_table.get_function(&FunctionKey::new("_tick")).unwrap().function;
 
 		let reset_function_value = unsafe {
@@ -156,7 +207,7 @@ impl Module {
 			let location = LLVMBuildAlloca(
 				builder.get_builder(),
 				LLVMPointerType(
-					LLVMFunctionType(self.to_llvm_type(Type::Float(0)), [].as_mut_ptr(), 0 as u32, 0),
+					LLVMFunctionType(self.to_llvm_type(Type::Float(0, 64)), [].as_mut_ptr(), 0 as u32, 0),
 					0
 				),
 				self.string_table.to_llvm_string("tick_function")
@@ -171,7 +222,7 @@ impl Module {
 			LLVMBuildLoad2(
 				builder.get_builder(),
 				LLVMPointerType(
-					LLVMFunctionType(self.to_llvm_type(Type::Float(0)), [].as_mut_ptr(), 0 as u32, 0),
+					LLVMFunctionType(self.to_llvm_type(Type::Float(0, 64)), [].as_mut_ptr(), 0 as u32, 0),
 					0
 				),
 				location,
@@ -206,31 +257,36 @@ impl Module {
 			name_globals
 		};
 
-		// get the name and input/output numbers for `airt_register_function`
+		// get the name, input/output numbers, and the category counts of the outputs for `airt_register_function`
 		let mut args: Vec<Vec<Value>> = Vec::new();
+		let mut categories: Vec<Vec<u64>> = Vec::new();
 		for ((_, function), name) in self.function_table.iter().zip(name_globals.iter()) {
 			if function.learned_values.len() == 0 {
 				continue;
 			}
 
-			if function.argument_types.len() == 0 {
-				panic!("Learned value container function needs more than 0 arguments");
+			let input_count = self.get_airt_input_count(function);
+			if input_count == 0 {
+				panic!("Learned value container function needs more than 0 number inputs");
 			}
 
 			args.push(vec![
 				*name,
-				self.create_immediate_integer(function.argument_types.len() as u64),
-				self.create_immediate_integer(function.learned_values.len() as u64),
+				self.create_immediate_integer(input_count as i64),
+				self.create_immediate_integer(function.learned_values.len() as i64),
 			]);
+
+			categories.push(function.learned_value_categories.iter().map(|&count| count as u64).collect());
 		}
 
 		// call `airt_register_function`
-		for mut arg in args {
+		for (mut arg, categories) in args.into_iter().zip(categories.iter()) {
+			arg.push(self.create_global_integer_array(categories));
 			self.add_function_call(main_block, &airt_register_function, &mut arg);
 		}
 
-		// call `_main`
-		self.add_function_call(main_block, &FunctionKey::new("_main"), &mut []);
+		// run the first episode
+		self.add_function_call(main_block, &episode_function, &mut []);
 
 		// call `airt_train`
 		self.add_function_call(main_block, &airt_train, &mut []);
@@ -305,14 +361,21 @@ impl Module {
 		}
 	}
 
-	pub fn add_branch_if_true(&mut self, block: Block, value: Value, if_true: Block, if_false: Block) {
+	// conditions have to be booleans or integers. floats have to be compared against something first
+	pub fn add_branch_if_true(
+		&mut self, block: Block, value: Value, if_true: Block, if_false: Block
+	) -> Result<(), MathError> {
+		if let Type::Float(_, _) = value.type_enum {
+			return Err(MathError::IncompatibleTypes(Type::Integer(0, 1), value.type_enum));
+		}
+
 		unsafe {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
 			let instruction = LLVMBuildCondBr(
 				builder.get_builder(),
-				self.math_resolve_value(block, value, Type::Integer(0, 1)).value,
+				self.add_cast(block, value, Type::Integer(0, 1))?.value, // integers are tested against zero
 				if_true.get_block(),
 				if_false.get_block()
 			);
@@ -326,6 +389,46 @@ impl Module {
 				}
 			);
 		}
+
+		Ok(())
+	}
+
+	// jumps to the block of the case that matches `value`, or to `default` if no case matches
+	pub fn add_switch(
+		&mut self, block: Block, value: Value, default: Block, cases: &[(u64, Block)]
+	) -> Result<(), MathError> {
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			let value = self.resolve_value(block, value);
+			if !value.type_enum.is_integer() {
+				return Err(MathError::IncompatibleTypes(Type::Integer(0, 64), value.type_enum));
+			}
+
+			let value = self.convert_to_type(block, value, Type::Integer(0, 64))?;
+
+			let instruction = LLVMBuildSwitch(
+				builder.get_builder(),
+				value.value,
+				default.get_block(),
+				cases.len() as u32
+			);
+
+			for &(case, target) in cases {
+				LLVMAddCase(instruction, self.create_immediate_integer(case as i64).value, target.get_block());
+			}
+
+			self.set_block_terminal(
+				block,
+				TerminalInstruction::Switch {
+					default: default.get_block(),
+					instruction,
+				}
+			);
+
+			Ok(())
+		}
 	}
 
 	pub fn get_context(&self) -> LLVMContextRef {
@@ -341,6 +444,12 @@ impl Module {
 	pub fn transform_function_name(&self, name: &str) -> String {
 		return format!("_{}", name);
 	}
+
+	// methods are named after the struct they belong to, so `car.update` becomes `_car.update` after the function name
+	// transformation. this is also the name the airt sees
+	pub fn transform_method_name(&self, type_index: usize, name: &str) -> String {
+		format!("{}.{}", self.lookup_struct_name(type_index), name)
+	}
 }
 
 impl Drop for Module {
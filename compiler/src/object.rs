@@ -15,6 +15,7 @@ use crate::{ Block, Builder, Module, Type, Value, };
 #[derive(Debug)]
 pub(crate) struct StructType {
  pub(crate) name: String,
+ pub(crate) property_to_default: HashMap<String, Value>, // constants that `new` stores into the property after allocation
  pub(crate) property_to_index: HashMap<String, usize>,
  pub(crate) property_to_type: HashMap<String, Type>,
  pub(crate) size: usize,
@@ -22,28 +23,73 @@ pub(crate) struct StructType {
  pub(crate) type_ref: LLVMTypeRef,
 }
 
+// provides everything that is needed to talk to LLVM concerning a tuple type. tuples are LLVM literal structs, so two
+// tuples with the same element types are the same type
+#[derive(Debug)]
+pub(crate) struct TupleType {
+	pub(crate) element_types: Vec<Type>,
+	pub(crate) type_ref: LLVMTypeRef,
+}
+
+// provides everything that is needed to talk to LLVM concerning a fixed size array type
+#[derive(Debug)]
+pub(crate) struct ArrayType {
+	pub(crate) element_type: Type,
+	pub(crate) size: usize,
+	pub(crate) type_ref: LLVMTypeRef,
+}
+
 #[derive(Debug, Default)]
 pub struct TypeTable {
+	arrays: Vec<ArrayType>,
+	enums: HashMap<String, Vec<String>>, // enum name -> variant names, a variant's discriminant is its position
 	index_to_struct: Vec<String>,
 	structs: HashMap<String, StructType>,
+	tuples: Vec<TupleType>,
 }
 
 impl Module {
-	// creates a struct type by calculating its size and then throwing it into the type table
-	pub fn create_struct_type(&mut self, name: &str, properties: HashMap<String, Type>) {
+	// creates a struct type by calculating its size and then throwing it into the type table. properties are laid out in
+	// the order they are given in, so the layout can be matched by the host
+	pub fn create_struct_type(&mut self, name: &str, properties: Vec<(String, Type)>, defaults: HashMap<String, Value>) {
 		unsafe {
 			let type_ref = LLVMStructCreateNamed(self.get_context(), self.string_table.to_llvm_string(&format!("struct.{}", name)));
 
 			let mut property_to_index = HashMap::new();
 			let mut property_to_type = HashMap::new();
 			let mut arguments = Vec::new();
-			let mut counter = 0;
-			for (name, &arg_type) in properties.iter() {
+			for (index, (property, arg_type)) in properties.into_iter().enumerate() {
+				if property_to_index.contains_key(&property) {
+					panic!("Struct `{}` defines field `{}` more than once", name, property);
+				}
+
 				arguments.push(self.to_llvm_type(arg_type));
-				property_to_index.insert(name.to_string(), counter);
-				property_to_type.insert(name.to_string(), arg_type);
+				property_to_index.insert(property.clone(), index);
+				property_to_type.insert(property, arg_type);
+			}
 
-				counter += 1;
+			// defaults are stored by every `new`, so they are converted to the property's type once up front. a default
+			// of an array is stored into every element, and arrays without one are zeroed
+			let mut defaults = defaults.into_iter()
+				.map(|(property, value)| {
+					let property_type = property_to_type[&property];
+					let value = if let Type::Array(_, _) = property_type {
+						self.create_filled_array(property_type, value)
+					} else {
+						self.convert_constant(value, property_type).unwrap_or(value)
+					};
+
+					(property, value)
+				})
+				.collect::<HashMap<String, Value>>();
+
+			for (property, &property_type) in property_to_type.iter() {
+				if let (Type::Array(_, _), false) = (property_type, defaults.contains_key(property)) {
+					defaults.insert(property.clone(), Value {
+						type_enum: property_type,
+						value: LLVMConstNull(self.to_llvm_type(property_type)),
+					});
+				}
 			}
 
 			LLVMStructSetBody(type_ref, arguments.as_mut_ptr(), arguments.len() as u32, 0);
@@ -54,6 +100,7 @@ impl Module {
 				name.to_string(),
 				StructType {
 					name: name.to_string(),
+					property_to_default: defaults,
 					property_to_index,
 					property_to_type,
 					size: (LLVMSizeOfTypeInBits(data_layout, type_ref) / 8) as usize,
@@ -77,7 +124,7 @@ impl Module {
 		*/
 	}
 
-	// allocates a struct
+	// allocates a struct and stores the default values of its properties
 	pub fn add_struct_malloc(&mut self, block: Block, struct_type_name: &str) -> Value {
 		unsafe {
 			let builder = Builder::new();
@@ -85,19 +132,23 @@ impl Module {
 
 			let struct_size = self.type_table.structs.get(struct_type_name).unwrap().size;
 
-			let malloc = self.add_function_call(
-				block,
-				&FunctionKey {
-					name: String::from("malloc"),
-				},
-				&mut [Value {
-					type_enum: Type::Integer(0, 32),
-					value: LLVMConstInt(LLVMIntType(32), struct_size as u64, 0),
-				}],
-			);
+			let malloc = if self.has_arena() {
+				self.add_arena_malloc(block, struct_size).unwrap()
+			} else {
+				self.add_function_call(
+					block,
+					&FunctionKey {
+						name: String::from("malloc"),
+					},
+					&mut [Value {
+						type_enum: Type::Integer(0, 32),
+						value: LLVMConstInt(LLVMIntType(32), struct_size as u64, 0),
+					}],
+				)
+			};
 
 			let struct_type = self.type_table.structs.get(struct_type_name).unwrap();
-			Value {
+			let object = Value {
 				type_enum: Type::Struct(1, struct_type.type_index),
 				value: LLVMBuildBitCast(
 					builder.get_builder(),
@@ -105,10 +156,77 @@ impl Module {
 					LLVMPointerType(struct_type.type_ref, 0),
 					self.string_table.to_llvm_string("structcast"),
 				),
+			};
+
+			let mut defaults = struct_type.property_to_default.iter()
+				.map(|(property, &value)| (property.clone(), value))
+				.collect::<Vec<(String, Value)>>();
+
+			defaults.sort_by_key(|(property, _)| struct_type.property_to_index[property]);
+
+			for (property, value) in defaults {
+				if let Type::Array(_, _) = value.type_enum { // arrays are stored inline, so we store into their location
+					let location = self.get_obj_property(block, object, &property).unwrap();
+					LLVMBuildStore(builder.get_builder(), value.value, location.value);
+				} else {
+					self.add_store_to_obj(block, object, &property, value).unwrap();
+				}
 			}
+
+			object
 		}
 	}
 
+	// creates a constant array with every element set to `value`, arrays nested in arrays are filled as well
+	fn create_filled_array(&self, array_type: Type, value: Value) -> Value {
+		let Ok(element_type) = self.get_array_element_type(array_type) else {
+			return self.convert_constant(value, array_type).unwrap();
+		};
+
+		let element = self.create_filled_array(element_type, value);
+		let mut elements = vec![element.value; self.get_array_size(array_type).unwrap()];
+
+		Value {
+			type_enum: array_type,
+			value: unsafe {
+				LLVMConstArray(self.to_llvm_type(element_type), elements.as_mut_ptr(), elements.len() as u32)
+			},
+		}
+	}
+
+	// frees a struct. objects in the arena are only freed when the arena is reset
+	pub fn add_struct_free(&mut self, block: Block, obj: Value) -> Result<(), MathError> {
+		let Type::Struct(_, _) = obj.type_enum else {
+			return Err(MathError::UnsupportedOperation);
+		};
+
+		if self.has_arena() {
+			return Ok(());
+		}
+
+		let object = self.resolve_value(block, obj);
+		let free = self.create_extern_function("free", &vec![Type::Void(1)], Type::Void(0));
+
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			let object = Value {
+				type_enum: Type::Void(1),
+				value: LLVMBuildBitCast(
+					builder.get_builder(),
+					object.value,
+					self.to_llvm_type(Type::Void(1)),
+					self.string_table.to_llvm_string("freecast")
+				),
+			};
+
+			self.add_function_call(block, &free, &mut [object]);
+		}
+
+		Ok(())
+	}
+
 	pub fn add_store_to_obj(
 		&mut self,
 		block: Block,
@@ -130,7 +248,7 @@ impl Module {
 				.get(type_name)
 				.unwrap();
 
-			let property_type = struct_type
+			let property_type = *struct_type
 				.property_to_type
 				.get(property)
 				.unwrap();
@@ -140,23 +258,29 @@ impl Module {
 				.get(property)
 				.unwrap();
 
-			let object_location = LLVMBuildLoad2(
-				builder.get_builder(),
-				self.to_llvm_type(obj.type_enum),
-				obj.value,
-				self.string_table.to_llvm_string(&format!("{}load", type_name))
-			);
+			let struct_type_ref = struct_type.type_ref;
+			let type_name = type_name.clone();
+
+			if let Type::Array(_, _) = property_type { // arrays can only be assigned element by element
+				return Err(MathError::UnsupportedOperation);
+			}
+
+			// resolve & convert type, compound assignments can produce values that differ from the property's type
+			let property_value = self.math_resolve_value(block, property_value, property_type)?;
+
+			// objects may be handed to us through a variable, a property, an array element, or a function's return
+			let object_location = self.resolve_value(block, obj).value;
 
 			let property_location = LLVMBuildStructGEP2(
 				builder.get_builder(),
-				struct_type.type_ref,
+				struct_type_ref,
 				object_location,
 				property_index as u32,
 				self.string_table.to_llvm_string(&format!("{}.{}", type_name, property))
 			);
 
 			Ok(Value {
-				type_enum: *property_type,
+				type_enum: property_type,
 				value: LLVMBuildStore(builder.get_builder(), property_value.value, property_location),
 			})
 		}
@@ -192,21 +316,27 @@ impl Module {
 				.get(property)
 				.unwrap();
 
-			let object_location = LLVMBuildLoad2(
-				builder.get_builder(),
-				self.to_llvm_type(obj.type_enum),
-				obj.value,
-				self.string_table.to_llvm_string(&format!("{}load", type_name))
-			);
+			let struct_type_ref = struct_type.type_ref;
+			let type_name = type_name.clone();
+
+			let object_location = self.resolve_value(block, obj).value;
 
 			let gep2 = LLVMBuildStructGEP2(
 				builder.get_builder(),
-				struct_type.type_ref,
+				struct_type_ref,
 				object_location,
 				property_index as u32,
 				self.string_table.to_llvm_string(&format!("{}.{}", type_name, property))
 			);
 
+			// arrays are stored inline, so we hand out the location of the array instead of its value
+			if let Type::Array(_, _) = property_type {
+				return Ok(Value {
+					type_enum: property_type.increment_pointer_number(),
+					value: gep2,
+				});
+			}
+
 			Ok(Value {
 				type_enum: property_type,
 				value: LLVMBuildLoad2(
@@ -219,6 +349,45 @@ impl Module {
 		}
 	}
 
+	// looks up the names of the float & integer properties of a struct, ordered by their position in the struct
+	pub fn get_number_properties(&self, type_index: usize) -> Vec<String> {
+		let struct_type = self.lookup_struct_type(type_index);
+
+		let mut properties = struct_type.property_to_type.iter()
+			.filter(|(_, property_type)| matches!(property_type, Type::Float(0, _) | Type::Integer(0, _) | Type::UnsignedInteger(0, _)))
+			.map(|(name, _)| name.clone())
+			.collect::<Vec<String>>();
+
+		properties.sort_by_key(|name| struct_type.property_to_index[name]);
+		properties
+	}
+
+	// looks up the names of all properties of a struct, ordered by their position in the struct
+	pub fn get_properties(&self, type_index: usize) -> Vec<String> {
+		let struct_type = self.lookup_struct_type(type_index);
+
+		let mut properties = struct_type.property_to_type.keys().cloned().collect::<Vec<String>>();
+		properties.sort_by_key(|name| struct_type.property_to_index[name]);
+		properties
+	}
+
+	pub fn get_property_type(&self, type_index: usize, property: &str) -> Option<Type> {
+		self.lookup_struct_type(type_index).property_to_type.get(property).copied()
+	}
+
+	pub fn has_property_default(&self, type_index: usize, property: &str) -> bool {
+		self.lookup_struct_type(type_index).property_to_default.contains_key(property)
+	}
+
+	pub fn get_struct_count(&self) -> usize {
+		self.type_table.index_to_struct.len()
+	}
+
+	// looks up the struct name from struct type index
+	pub fn lookup_struct_name(&self, type_index: usize) -> &str {
+		&self.type_table.index_to_struct[type_index]
+	}
+
 	// looks up the struct type index from struct name
 	pub fn lookup_struct_type_index(&self, type_name: &str) -> usize {
 		self.type_table.structs.get(type_name).unwrap().type_index
@@ -228,4 +397,174 @@ impl Module {
 		let type_name = &self.type_table.index_to_struct[type_index];
 		self.type_table.structs.get(type_name).unwrap()
 	}
+
+	// enums are lowered to integers, so the type table only needs to remember the names of the variants
+	pub fn create_enum_type(&mut self, name: &str, variants: Vec<String>) {
+		if self.type_table.enums.contains_key(name) || self.type_table.structs.contains_key(name) {
+			panic!("Type `{}` is already defined", name);
+		}
+
+		for (i, variant) in variants.iter().enumerate() {
+			if variants[..i].contains(variant) {
+				panic!("Enum `{}` defines variant `{}` more than once", name, variant);
+			}
+		}
+
+		self.type_table.enums.insert(name.to_string(), variants);
+	}
+
+	pub fn get_enum_variants(&self, name: &str) -> Option<&Vec<String>> {
+		self.type_table.enums.get(name)
+	}
+
+	// looks up the discriminant of an enum variant
+	pub fn get_enum_discriminant(&self, name: &str, variant: &str) -> Result<u64, MathError> {
+		self.type_table.enums.get(name)
+			.and_then(|variants| variants.iter().position(|other| other == variant))
+			.map(|discriminant| discriminant as u64)
+			.ok_or_else(|| MathError::UndefinedVariable(format!("{}::{}", name, variant)))
+	}
+
+	// creates the integer constant for an enum variant
+	pub fn get_enum_variant(&self, name: &str, variant: &str) -> Result<Value, MathError> {
+		Ok(self.create_immediate_integer(self.get_enum_discriminant(name, variant)? as i64))
+	}
+
+	// looks up the array type for an element type & size, creating it if it does not exist yet
+	pub fn get_array_type(&mut self, element_type: Type, size: usize) -> Type {
+		if let Some(index) = self.type_table.arrays.iter()
+			.position(|array| array.element_type == element_type && array.size == size)
+		{
+			return Type::Array(0, index);
+		}
+
+		let type_ref = unsafe {
+			LLVMArrayType(self.to_llvm_type(element_type), size as u32)
+		};
+
+		self.type_table.arrays.push(ArrayType {
+			element_type,
+			size,
+			type_ref,
+		});
+
+		Type::Array(0, self.type_table.arrays.len() - 1)
+	}
+
+	pub fn get_array_element_type(&self, type_enum: Type) -> Result<Type, MathError> {
+		let Type::Array(_, index) = type_enum else {
+			return Err(MathError::UnsupportedOperation);
+		};
+
+		Ok(self.lookup_array_type(index).element_type)
+	}
+
+	pub fn get_array_size(&self, type_enum: Type) -> Result<usize, MathError> {
+		let Type::Array(_, index) = type_enum else {
+			return Err(MathError::UnsupportedOperation);
+		};
+
+		Ok(self.lookup_array_type(index).size)
+	}
+
+	pub(crate) fn lookup_array_type(&self, type_index: usize) -> &ArrayType {
+		&self.type_table.arrays[type_index]
+	}
+
+	// looks up the tuple type for a list of element types, creating it if it does not exist yet
+	pub fn get_tuple_type(&mut self, element_types: Vec<Type>) -> Type {
+		if let Some(index) = self.type_table.tuples.iter().position(|tuple| tuple.element_types == element_types) {
+			return Type::Tuple(0, index);
+		}
+
+		let type_ref = unsafe {
+			let mut elements = element_types.iter()
+				.map(|&element_type| self.to_llvm_type(element_type))
+				.collect::<Vec<_>>();
+
+			LLVMStructTypeInContext(self.get_context(), elements.as_mut_ptr(), elements.len() as u32, 0)
+		};
+
+		self.type_table.tuples.push(TupleType {
+			element_types,
+			type_ref,
+		});
+
+		Type::Tuple(0, self.type_table.tuples.len() - 1)
+	}
+
+	pub fn get_tuple_element_types(&self, type_enum: Type) -> Result<Vec<Type>, MathError> {
+		let Type::Tuple(_, index) = type_enum else {
+			return Err(MathError::UnsupportedOperation);
+		};
+
+		Ok(self.lookup_tuple_type(index).element_types.clone())
+	}
+
+	pub fn get_tuple_count(&self) -> usize {
+		self.type_table.tuples.len()
+	}
+
+	pub(crate) fn lookup_tuple_type(&self, type_index: usize) -> &TupleType {
+		&self.type_table.tuples[type_index]
+	}
+
+	// packs values into a tuple, the tuple's type is made out of the types of the values
+	pub fn add_tuple(&mut self, block: Block, values: &[Value]) -> Value {
+		let values = values.iter().map(|&value| self.resolve_value(block, value)).collect::<Vec<Value>>();
+		let tuple_type = self.get_tuple_type(values.iter().map(|value| value.type_enum).collect());
+
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			let mut tuple = LLVMGetUndef(self.to_llvm_type(tuple_type));
+			for (index, value) in values.iter().enumerate() {
+				tuple = LLVMBuildInsertValue(
+					builder.get_builder(), tuple, value.value, index as u32, self.string_table.to_llvm_string("tuple")
+				);
+			}
+
+			Value {
+				type_enum: tuple_type,
+				value: tuple,
+			}
+		}
+	}
+
+	pub fn get_tuple_element(&mut self, block: Block, tuple: Value, index: usize) -> Result<Value, MathError> {
+		let tuple = self.resolve_value(block, tuple);
+		let element_types = self.get_tuple_element_types(tuple.type_enum)?;
+		let Some(&element_type) = element_types.get(index) else {
+			return Err(MathError::UnsupportedOperation);
+		};
+
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			Ok(Value {
+				type_enum: element_type,
+				value: LLVMBuildExtractValue(
+					builder.get_builder(), tuple.value, index as u32, self.string_table.to_llvm_string("element")
+				),
+			})
+		}
+	}
+
+	// converts a tuple into a tuple with the same number of elements, element by element
+	pub(crate) fn convert_tuple_to_type(&mut self, block: Block, tuple: Value, result_type: Type) -> Result<Value, MathError> {
+		let element_types = self.get_tuple_element_types(result_type)?;
+		if self.get_tuple_element_types(tuple.type_enum)?.len() != element_types.len() {
+			return Err(MathError::IncompatibleTypes(result_type, tuple.type_enum));
+		}
+
+		let mut values = Vec::new();
+		for (index, &element_type) in element_types.iter().enumerate() {
+			let element = self.get_tuple_element(block, tuple, index)?;
+			values.push(self.convert_to_type(block, element, element_type)?);
+		}
+
+		Ok(self.add_tuple(block, &values))
+	}
 }
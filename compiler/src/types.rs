@@ -3,106 +3,177 @@ pub type Pointers = u8;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Type {
+	Array(Pointers, usize), // index into the type table's array types
 	CString(Pointers),
-	Float(Pointers),
-	FloatArray(usize),
-	Integer(Pointers, Bits),
+	Float(Pointers, Bits),
+	Integer(Pointers, Bits), // signed, except for booleans which are `Integer(_, 1)`
 	Struct(Pointers, usize),
+	Tuple(Pointers, usize), // index into the type table's tuple types
+	UnsignedInteger(Pointers, Bits),
 	Void(Pointers),
 }
 
 impl Type {
 	pub fn increment_pointer_number(self) -> Self {
 		match self {
+			Type::Array(p, index) => Type::Array(p + 1, index),
 			Type::CString(p) => Type::CString(p + 1),
-			Type::Float(p) => Type::Float(p + 1),
-			Type::FloatArray(size) => Type::FloatArray(size),
+			Type::Float(p, bits) => Type::Float(p + 1, bits),
 			Type::Integer(p, bits) => Type::Integer(p + 1, bits),
 			Type::Struct(p, index) => Type::Struct(p + 1, index),
+			Type::Tuple(p, index) => Type::Tuple(p + 1, index),
+			Type::UnsignedInteger(p, bits) => Type::UnsignedInteger(p + 1, bits),
 			Type::Void(p) => Type::Void(p + 1),
 		}
 	}
 
 	pub fn get_pointer_number(self) -> Pointers {
 		match self {
+			Type::Array(number, _) => number,
 			Type::CString(number) => number,
-			Type::Float(number) => number,
-			Type::FloatArray(_) => 1,
+			Type::Float(number, _) => number,
 			Type::Integer(number, _) => number,
 			Type::Struct(number, _) => number,
+			Type::Tuple(number, _) => number,
+			Type::UnsignedInteger(number, _) => number,
 			Type::Void(p) => p,
 		}
 	}
 
 	pub fn zero_pointer_number(self) -> Self {
 		match self {
+			Type::Array(_, index) => Type::Array(0, index),
 			Type::CString(_) => Type::CString(0),
-			Type::Float(_) => Type::Float(0),
-			Type::FloatArray(size) => Type::FloatArray(size),
+			Type::Float(_, bits) => Type::Float(0, bits),
 			Type::Integer(_, bits) => Type::Integer(0, bits),
 			Type::Struct(_, index) => Type::Struct(0, index),
+			Type::Tuple(_, index) => Type::Tuple(0, index),
+			Type::UnsignedInteger(_, bits) => Type::UnsignedInteger(0, bits),
 			Type::Void(_) => Type::Void(0),
 		}
 	}
 
 	pub fn zero_bits(self) -> Self {
 		match self {
+			Type::Array(pointer_number, index) => Type::Array(pointer_number, index),
 			Type::CString(pointer_number) => Type::CString(pointer_number),
-			Type::Float(pointer_number) => Type::Float(pointer_number),
-			Type::FloatArray(size) => Type::FloatArray(size),
+			Type::Float(pointer_number, _) => Type::Float(pointer_number, 0),
 			Type::Integer(pointer_number, _) => Type::Integer(pointer_number, 0),
 			Type::Struct(pointer_number, index) => Type::Struct(pointer_number, index),
+			Type::Tuple(pointer_number, index) => Type::Tuple(pointer_number, index),
+			Type::UnsignedInteger(pointer_number, _) => Type::UnsignedInteger(pointer_number, 0),
 			Type::Void(pointer_number) => Type::Void(pointer_number),
 		}
 	}
 
-	pub fn to_array(self, size: usize) -> Self {
-		match self {
-			Type::Float(_) => Type::FloatArray(size),
-			_ => todo!(),
+	pub fn is_integer(&self) -> bool {
+		matches!(self, Type::Integer(_, _) | Type::UnsignedInteger(_, _))
+	}
+
+	/// Whether or not integer math on this type is signed. Booleans are `Integer(_, 1)`, but they are never signed.
+	pub fn is_signed(&self) -> bool {
+		match *self {
+			Type::Integer(_, bits) => bits > 1,
+			_ => false,
 		}
 	}
 
-	pub fn to_scalar(self) -> Self {
-		match self {
-			Type::FloatArray(_) => Type::Float(0),
-			_ => todo!(),
+	pub fn get_bits(&self) -> Option<Bits> {
+		match *self {
+			Type::Float(_, bits) | Type::Integer(_, bits) | Type::UnsignedInteger(_, bits) => Some(bits),
+			_ => None,
 		}
 	}
 
 	/// Whether or not this type can be converted to another type.
 	pub fn is_compatible(&self, other: &Type) -> bool {
+		self.get_conversion(other).is_ok()
+	}
+
+	/// Looks up how a value of this type is converted into a value of another type. Pointer numbers are ignored, except for
+	/// arrays decaying into void pointers.
+	pub fn get_conversion(&self, other: &Type) -> Result<Conversion, MathError> {
 		if self == other {
-			return true;
+			return Ok(Conversion::Identity);
 		}
 
+		match (self.zero_pointer_number(), other.zero_pointer_number()) {
+			(Type::Array(_, _), Type::Void(_)) if *other == Type::Void(1) => Ok(Conversion::Decay),
+			(Type::Array(_, index1), Type::Array(_, index2)) if index1 == index2 => Ok(Conversion::Identity),
+			(Type::CString(_), Type::CString(_)) => Ok(Conversion::Identity),
+			(Type::Float(_, bits1), Type::Float(_, bits2)) => Ok(
+				if bits1 > bits2 {
+					Conversion::FloatTruncate
+				} else if bits1 < bits2 {
+					Conversion::FloatExtend
+				} else {
+					Conversion::Identity
+				}
+			),
+			(Type::Float(_, _), Type::Integer(_, 1)) => Ok(Conversion::FloatToBoolean),
+			(Type::Float(_, _), Type::Integer(_, _) | Type::UnsignedInteger(_, _)) => Ok(Conversion::FloatToInteger),
+			(
+				Type::Integer(_, bits1) | Type::UnsignedInteger(_, bits1),
+				Type::Integer(_, bits2) | Type::UnsignedInteger(_, bits2),
+			) => Ok(
+				if other.zero_pointer_number() == Type::Integer(0, 1) && bits1 != 1 {
+					Conversion::IntegerToBoolean
+				} else if bits1 > bits2 {
+					Conversion::Truncate
+				} else if bits1 < bits2 {
+					Conversion::Extend
+				} else if self.is_signed() == other.is_signed() {
+					Conversion::Identity
+				} else {
+					Conversion::Reinterpret
+				}
+			),
+			(Type::Integer(_, _) | Type::UnsignedInteger(_, _), Type::Float(_, _)) => Ok(Conversion::IntegerToFloat),
+			(Type::Struct(_, index1), Type::Struct(_, index2)) if index1 == index2 => Ok(Conversion::Identity),
+			(Type::Tuple(_, index1), Type::Tuple(_, index2)) if index1 == index2 => Ok(Conversion::Identity),
+			(Type::Void(_), Type::Void(_)) => Ok(Conversion::Identity),
+			_ => Err(MathError::IncompatibleTypes(*self, *other)),
+		}
+	}
+}
+
+/// The ways a value can be converted from one type to another, see `Type::get_conversion`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Conversion {
+	Decay, // arrays into void pointers
+	Extend, // integers into wider integers
+	FloatExtend, // floats into wider floats
+	FloatToBoolean, // compares against zero
+	FloatToInteger,
+	FloatTruncate, // floats into narrower floats
+	Identity,
+	IntegerToBoolean, // compares against zero
+	IntegerToFloat,
+	Reinterpret, // integers into integers of the same width but a different signedness
+	Truncate, // integers into narrower integers
+}
+
+impl Conversion {
+	/// Whether or not the conversion can change the value being converted. Integers that are too wide for a float's
+	/// mantissa are not counted, since `int` and `float` are mixed all the time.
+	pub fn loses_precision(&self) -> bool {
 		match *self {
-			Type::Float(_) => match *other {
-				Type::Float(_) => {
-					return true;
-				},
-				_ => todo!("{:?} {:?}", self, other),
-			},
-			Type::FloatArray(_) => match *other {
-				Type::Float(pointer) => {
-					pointer == 1
-				},
-				_ => todo!("{:?} {:?}", self, other),
-			},
-			Type::Integer(_, _) => match *other {
-				Type::Integer(_, _) => {
-					self.zero_pointer_number() == other.zero_pointer_number()
-				},
-				_ => todo!("{:?} {:?}", self, other),
-			},
-			_ => todo!("{:?} {:?}", self, other),
+			Conversion::FloatToBoolean
+				| Conversion::FloatToInteger
+				| Conversion::FloatTruncate
+				| Conversion::IntegerToBoolean
+				| Conversion::Reinterpret
+				| Conversion::Truncate => true,
+			_ => false,
 		}
 	}
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum MathError {
+	ImmutableVariable(String),
 	IncompatibleTypes(Type, Type),
+	UndefinedFunction(String),
 	UndefinedVariable(String),
 	UnsupportedOperation,
 }
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 use llvm_sys::core::*;
 use llvm_sys::prelude::*;
+use llvm_sys::LLVMLinkage;
 
-use crate::{ Block, Builder, MathError, Module, Type, Value };
+use crate::{ Block, Builder, FunctionKey, MathError, Module, Type, Value };
 
 #[derive(Debug)]
 pub struct Variable {
@@ -26,6 +27,20 @@ impl VariableTable {
 		self.variables.get_mut(&function).unwrap().insert(variable.name.clone(), variable);
 	}
 
+	// the names & types of the global variables, ordered by name
+	pub(crate) fn get_globals(&self) -> Vec<(String, Type)> {
+		let mut globals = self.variables.get(&std::ptr::null_mut())
+			.map(|variables| variables.values().map(|variable| (variable.name.clone(), variable.type_enum)).collect())
+			.unwrap_or(Vec::new());
+
+		globals.sort_by(|(a, _), (b, _)| a.cmp(b));
+		globals
+	}
+
+	pub fn contains(&self, function: LLVMValueRef, name: &str) -> bool {
+		self.variables.get(&function).is_some_and(|variables| variables.contains_key(name))
+	}
+
 	pub fn get(&mut self, function: LLVMValueRef, name: &str) -> Option<&Variable> {
 		if !self.variables.contains_key(&function) {
 			None
@@ -36,33 +51,26 @@ impl VariableTable {
 }
 
 impl Module {
-	pub fn add_immutable_variable(&mut self, block: Block, name: &str, type_enum: Type) -> Value {
-		// TODO we're going to use the stack, even for immutable variables, for ease of design
-		unsafe {
-			let builder = Builder::new();
-			builder.seek_to_end(block);
-
-			let value = Value {
-				type_enum: self.upgrade_type(type_enum),
-				value: LLVMBuildAlloca(
-					builder.get_builder(),
-					self.to_llvm_type(type_enum),
-					self.string_table.to_llvm_string(name)
-				),
-			};
+	// immutable variables are bound straight to their value, so they never touch the stack
+	pub fn add_immutable_variable(
+		&mut self, block: Block, name: &str, type_enum: Type, value: Value
+	) -> Result<Value, MathError> {
+		let value = self.math_resolve_value(block, value, type_enum)?; // resolve & convert type
+		if value.type_enum != type_enum {
+			return Err(MathError::IncompatibleTypes(type_enum, value.type_enum));
+		}
 
-			self.variable_table.add(
-				block.get_parent(),
-				Variable {
-					type_enum,
-					is_mutable: false,
-					name: String::from(name),
-					value,
-				}
-			);
+		self.variable_table.add(
+			block.get_parent(),
+			Variable {
+				type_enum,
+				is_mutable: false,
+				name: String::from(name),
+				value,
+			}
+		);
 
-			return value;
-		}
+		Ok(value)
 	}
 
 	pub fn add_immutable_array(&mut self, block: Block, type_enum: Type, element_count: usize) -> Value {
@@ -71,11 +79,13 @@ impl Module {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
+			let array_type = self.get_array_type(type_enum, element_count);
+
 			let value = Value {
-				type_enum: type_enum.to_array(element_count),
+				type_enum: array_type.increment_pointer_number(),
 				value: LLVMBuildAlloca(
 					builder.get_builder(),
-					LLVMArrayType(self.to_llvm_type(type_enum), element_count as u32),
+					self.to_llvm_type(array_type),
 					self.string_table.to_llvm_string("array")
 				),
 			};
@@ -90,11 +100,7 @@ impl Module {
 			builder.seek_to_end(block);
 
 			let llvm_type = self.to_llvm_type(type_enum);
-			let upgraded_type = if let Type::Struct(_, _) = type_enum {
-				type_enum
-			} else {
-				self.upgrade_type(type_enum)
-			};
+			let upgraded_type = self.upgrade_type(type_enum);
 
 			let value = Value {
 				type_enum: upgraded_type,
@@ -120,12 +126,17 @@ impl Module {
 	}
 
 	pub fn add_global_variable(&mut self, name: &str, type_enum: Type) -> Value {
+		self.add_global(name, type_enum, true)
+	}
+
+	// immutable globals still live in memory, since their initializer may have to run in the global constructor
+	pub fn add_immutable_global_variable(&mut self, name: &str, type_enum: Type) -> Value {
+		self.add_global(name, type_enum, false)
+	}
+
+	fn add_global(&mut self, name: &str, type_enum: Type, is_mutable: bool) -> Value {
 		unsafe {
-			let upgraded_type = if let Type::Struct(_, _) = type_enum {
-				type_enum
-			} else {
-				self.upgrade_type(type_enum)
-			};
+			let upgraded_type = self.upgrade_type(type_enum);
 
 			let value = Value {
 				type_enum: upgraded_type,
@@ -140,18 +151,18 @@ impl Module {
 				std::ptr::null_mut(),
 				Variable {
 					type_enum,
-					is_mutable: true,
+					is_mutable,
 					name: String::from(name),
 					value,
 				}
 			);
 
 			match type_enum {
-				Type::Float(_) => {
-					LLVMSetInitializer(value.value, LLVMConstReal(self.to_llvm_type(Type::Float(0)), 0.0));
+				Type::Float(_, _) => {
+					LLVMSetInitializer(value.value, LLVMConstReal(self.to_llvm_type(type_enum), 0.0));
 				}
-				Type::Integer(_, bits) => {
-					LLVMSetInitializer(value.value, LLVMConstInt(self.to_llvm_type(Type::Integer(0, bits)), 0, 0));
+				Type::Integer(_, _) | Type::UnsignedInteger(_, _) => {
+					LLVMSetInitializer(value.value, LLVMConstInt(self.to_llvm_type(type_enum), 0, 0));
 				},
 				Type::Struct(_, _) => {
 					LLVMSetInitializer(
@@ -162,6 +173,9 @@ impl Module {
 						),
 					);
 				},
+				Type::Array(_, _) | Type::CString(_) | Type::Tuple(_, _) => {
+					LLVMSetInitializer(value.value, LLVMConstNull(self.to_llvm_type(type_enum)));
+				},
 				_ => todo!(),
 			}
 
@@ -169,6 +183,167 @@ impl Module {
 		}
 	}
 
+	// replaces the zero initializer of a global variable with a constant
+	pub fn set_global_initializer(&mut self, global: Value, value: Value) -> Result<(), MathError> {
+		let type_enum = self.downgrade_type(global.type_enum);
+		let initializer = self.convert_constant(value, type_enum)?;
+
+		unsafe {
+			LLVMSetInitializer(global.value, initializer.value);
+		}
+
+		Ok(())
+	}
+
+	// rebuilds a constant at another width, so storing it does not need a conversion instruction. constants that do not
+	// fit into the new type exactly are not rebuilt
+	pub(crate) fn convert_constant(&self, value: Value, type_enum: Type) -> Result<Value, MathError> {
+		unsafe {
+			if LLVMIsConstant(value.value) == 0 {
+				return Err(MathError::UnsupportedOperation);
+			}
+
+			let integer = match value.type_enum {
+				Type::Integer(0, bits) if bits > 1 => Some(LLVMConstIntGetSExtValue(value.value) as i128),
+				Type::UnsignedInteger(0, _) => Some(LLVMConstIntGetZExtValue(value.value) as i128),
+				_ => None,
+			};
+
+			let constant = match (value.type_enum, type_enum, integer) {
+				(value_type, type_enum, _) if value_type == type_enum => value.value,
+				(Type::Float(0, _), Type::Float(0, bits), _) => {
+					let mut loses_info = 0;
+					let number = LLVMConstRealGetDouble(value.value, &mut loses_info);
+					if bits == 32 && (number as f32) as f64 != number && !number.is_nan() {
+						return Err(MathError::IncompatibleTypes(type_enum, value.type_enum));
+					}
+
+					LLVMConstReal(self.to_llvm_type(type_enum), number)
+				},
+				(_, Type::Integer(0, bits) | Type::UnsignedInteger(0, bits), Some(integer)) if bits > 1 => {
+					let (min, max) = if type_enum.is_signed() {
+						(-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+					} else {
+						(0, (1i128 << bits) - 1)
+					};
+
+					if integer < min || integer > max {
+						return Err(MathError::IncompatibleTypes(type_enum, value.type_enum));
+					}
+
+					LLVMConstInt(self.to_llvm_type(type_enum), integer as u64, type_enum.is_signed() as i32)
+				},
+				(_, Type::Float(0, bits), Some(integer)) => {
+					let mantissa_bits = if bits == 32 { 24 } else { 53 };
+					if integer.unsigned_abs() > 1u128 << mantissa_bits {
+						return Err(MathError::IncompatibleTypes(type_enum, value.type_enum));
+					}
+
+					LLVMConstReal(self.to_llvm_type(type_enum), integer as f64)
+				},
+				(value_type, type_enum, _) => return Err(MathError::IncompatibleTypes(type_enum, value_type)),
+			};
+
+			Ok(Value {
+				type_enum,
+				value: constant,
+			})
+		}
+	}
+
+	// global variables that cannot be initialized with a constant are initialized by a function that the generated main
+	// calls before `_main`. returns the block that initializers should be compiled into
+	pub fn get_global_constructor(&mut self) -> (FunctionKey, Block) {
+		if let Some(constructor) = &self.global_constructor {
+			return constructor.clone();
+		}
+
+		let function = self.create_function("init.globals", &vec![], Type::Void(0));
+		let block = self.new_block("init.globals", &function);
+
+		self.global_constructor = Some((function.clone(), block));
+		(function, block)
+	}
+
+	// the global constructor runs before every episode, so a global that has a constant initializer is set back to it
+	// there as well
+	pub fn add_global_reset(&mut self, global: Value) {
+		let (_, block) = self.get_global_constructor();
+
+		unsafe {
+			let builder = Builder::new();
+			builder.seek_to_end(block);
+
+			LLVMBuildStore(builder.get_builder(), LLVMGetInitializer(global.value), global.value);
+		}
+	}
+
+	// initializers may create blocks of their own, so the next initializer has to continue in the block the last one
+	// finished in
+	pub fn set_global_constructor_block(&mut self, block: Block) {
+		self.global_constructor.as_mut().unwrap().1 = block;
+	}
+
+	// declares a global variable that is owned by the host, so it is not given an initializer
+	pub fn add_extern_global_variable(&mut self, name: &str, type_enum: Type) -> Value {
+		unsafe {
+			let value = Value {
+				type_enum: self.upgrade_type(type_enum),
+				value: LLVMAddGlobal(
+					self.get_module(),
+					self.to_llvm_type(type_enum),
+					self.string_table.to_llvm_string(name)
+				),
+			};
+
+			self.variable_table.add(
+				std::ptr::null_mut(),
+				Variable {
+					type_enum,
+					is_mutable: true,
+					name: String::from(name),
+					value,
+				}
+			);
+
+			value
+		}
+	}
+
+	// creates a constant array of integers, used to hand tables of numbers to the runtime
+	pub fn create_global_integer_array(&mut self, values: &[u64]) -> Value {
+		unsafe {
+			let array_type = self.get_array_type(Type::Integer(0, 64), values.len());
+
+			let mut elements = values.iter()
+				.map(|&value| self.create_immediate_integer(value as i64).value)
+				.collect::<Vec<LLVMValueRef>>();
+
+			let global = LLVMAddGlobal(
+				self.get_module(),
+				self.to_llvm_type(array_type),
+				self.string_table.to_llvm_string("")
+			);
+
+			LLVMSetInitializer(
+				global,
+				LLVMConstArray(self.to_llvm_type(Type::Integer(0, 64)), elements.as_mut_ptr(), elements.len() as u32)
+			);
+			LLVMSetGlobalConstant(global, 1);
+			LLVMSetLinkage(global, LLVMLinkage::LLVMPrivateLinkage);
+
+			Value {
+				type_enum: array_type.increment_pointer_number(),
+				value: global,
+			}
+		}
+	}
+
+	// whether `name` is an argument or a local variable of the function `block` belongs to
+	pub fn has_local_variable(&self, block: Block, name: &str) -> bool {
+		self.variable_table.contains(block.get_parent(), name)
+	}
+
 	pub fn get_variable(&mut self, block: Block, name: &str) -> Result<Value, MathError> {
 		if let Some(variable) = self.variable_table.get(block.get_parent(), name) {
 			return Ok(variable.value);
@@ -181,52 +356,67 @@ impl Module {
 		Err(MathError::UndefinedVariable(String::from(name)))
 	}
 
+	// the location of a variable that is about to be assigned to, immutable variables cannot be assigned to
+	pub fn get_variable_location(&mut self, block: Block, name: &str) -> Result<Value, MathError> {
+		let function = if self.variable_table.get(block.get_parent(), name).is_some() {
+			block.get_parent()
+		} else {
+			std::ptr::null_mut()
+		};
+
+		match self.variable_table.get(function, name) {
+			Some(variable) if variable.is_mutable => Ok(variable.value),
+			Some(_) => Err(MathError::ImmutableVariable(String::from(name))),
+			None => Err(MathError::UndefinedVariable(String::from(name))),
+		}
+	}
+
 	pub fn add_store(&mut self, block: Block, location: Value, value: Value) -> Result<Value, MathError> {
 		unsafe {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			if let Type::Struct(_, _) = value.type_enum {
-				Ok(Value {
-					type_enum: Type::Void(0),
-					value: LLVMBuildStore(builder.get_builder(), value.value, location.value),
-				})
-			} else {
-				let value = self.math_resolve_value(block, value, location.type_enum); // resolve & convert type
-				if value.type_enum != self.downgrade_type(location.type_enum) {
-					return Err(MathError::IncompatibleTypes(location.type_enum, value.type_enum));
-				}
-
-				Ok(Value {
-					type_enum: Type::Void(0),
-					value: LLVMBuildStore(builder.get_builder(), value.value, location.value),
-				})
+			let value_type = self.downgrade_type(location.type_enum);
+			let value = self.math_resolve_value(block, value, value_type)?; // resolve & convert type
+			if value.type_enum != value_type {
+				return Err(MathError::IncompatibleTypes(location.type_enum, value.type_enum));
 			}
+
+			Ok(Value {
+				type_enum: Type::Void(0),
+				value: LLVMBuildStore(builder.get_builder(), value.value, location.value),
+			})
 		}
 	}
 
-	pub fn add_store_to_array(
-		&mut self, block: Block, array: Value, index: usize, value: Value
-	) -> Result<Value, MathError> {
+	// calculates the location of an array element. `array` is the location of the array, the returned value is the
+	// location of the element
+	pub fn add_array_element(&mut self, block: Block, array: Value, index: Value) -> Result<Value, MathError> {
 		unsafe {
 			let builder = Builder::new();
 			builder.seek_to_end(block);
 
-			let scalar = array.type_enum.to_scalar();
+			let Type::Array(1, _) = array.type_enum else {
+				return Err(MathError::UnsupportedOperation);
+			};
+
+			let element_type = self.get_array_element_type(array.type_enum)?;
 
-			let value = self.math_resolve_value(block, value, scalar); // resolve & convert type
-			if value.type_enum != scalar {
-				return Err(MathError::IncompatibleTypes(scalar, value.type_enum));
+			let index = self.resolve_value(block, index);
+			if !index.type_enum.is_integer() {
+				return Err(MathError::IncompatibleTypes(Type::Integer(0, 64), index.type_enum));
 			}
 
+			let index = self.convert_to_type(block, index, Type::Integer(0, 64))?;
+
 			let mut args = [
 				LLVMConstInt(self.to_llvm_type(Type::Integer(0, 64)), 0, 0),
-				LLVMConstInt(self.to_llvm_type(Type::Integer(0, 64)), index as u64, 0),
+				index.value,
 			];
 
 			let array_element = LLVMBuildGEP2(
 				builder.get_builder(),
-				self.to_llvm_type(array.type_enum),
+				self.to_llvm_type(array.type_enum.zero_pointer_number()),
 				array.value,
 				args.as_mut_ptr(),
 				2,
@@ -234,18 +424,36 @@ impl Module {
 			);
 
 			Ok(Value {
-				type_enum: Type::Void(0),
-				value: LLVMBuildStore(builder.get_builder(), value.value, array_element),
+				type_enum: self.upgrade_type(element_type),
+				value: array_element,
 			})
 		}
 	}
 
+	// loads an array element. arrays nested in arrays are not loaded, their location is returned instead
+	pub fn get_array_element(&mut self, block: Block, array: Value, index: Value) -> Result<Value, MathError> {
+		let element = self.add_array_element(block, array, index)?;
+
+		if let Type::Array(_, _) = element.type_enum {
+			Ok(element)
+		} else {
+			Ok(self.resolve_value(block, element))
+		}
+	}
+
+	pub fn add_store_to_array(
+		&mut self, block: Block, array: Value, index: Value, value: Value
+	) -> Result<Value, MathError> {
+		let element = self.add_array_element(block, array, index)?;
+		self.add_store(block, element, value)
+	}
+
 	pub fn add_argument(&mut self, block: Block, name: &str, type_enum: Type, value: Value) {
 		self.variable_table.add(
 			block.get_parent(),
 			Variable {
 				type_enum,
-				is_mutable: true,
+				is_mutable: false,
 				name: String::from(name),
 				value,
 			}